@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 /// Describes component trigger action.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ComponentTriggerAction {
     /// Type of the action to trigger.
     pub action: String,
@@ -9,7 +9,7 @@ pub struct ComponentTriggerAction {
     pub component: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Component {
     /// Identifier of the project component.
     pub id: String,
@@ -20,4 +20,12 @@ pub struct Component {
     pub properties: HashMap<String, String>,
     /// Component trigger <-> corresponding actions map.
     pub triggers: HashMap<String, Vec<ComponentTriggerAction>>,
+    /// Version of the component schema this component has been authored against, components
+    /// without version are pinned to the current version once project is saved.
+    #[serde(
+        default,
+        rename(serialize = "schemaVersion", deserialize = "schemaVersion"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub schema_version: Option<i64>,
 }
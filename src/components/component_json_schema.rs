@@ -0,0 +1,309 @@
+use serde_json::{self, Map, Value};
+
+use components::component_property_schema::{ComponentPropertySchema, ComponentPropertyValueKind};
+use components::component_property_value::SI_PREFIXES;
+use components::component_schema::ComponentSchema;
+use json_patch::pointer::escape_token;
+
+/// Version of the JSON Schema specification generated documents conform to.
+const JSON_SCHEMA_DRAFT: &str = "http://json-schema.org/draft-07/schema#";
+
+/// Pattern of the numbers in canonical form, see `format_number`.
+const NUMBER_PATTERN: &str = r"-?[0-9]+(\.[0-9]+)?";
+
+/// Translates component schema into JSON Schema document that describes valid component of that
+/// type. Property values are described in their canonical form (see
+/// `ComponentPropertyValueKind::normalize_value`), constraints that JSON Schema can't express
+/// (ranges, units and allowed component types) are kept in the `x-kind` extension keyword.
+///
+/// # Arguments
+///
+/// * `schema` - Component schema to translate, with inheritance already resolved.
+/// * `schemas` - List of all known component schemas, used to list actions that triggers can
+/// perform.
+pub fn component_json_schema(schema: &ComponentSchema, schemas: &[ComponentSchema]) -> Value {
+    let mut document = component_definition(schema, &trigger_actions(schemas));
+    document["$schema"] = json!(JSON_SCHEMA_DRAFT);
+
+    document
+}
+
+/// Translates all non-abstract component schemas into a single JSON Schema document, every
+/// component schema becomes a definition and valid component should match one of them.
+///
+/// # Arguments
+///
+/// * `schemas` - List of all known component schemas, with inheritance already resolved.
+pub fn component_json_schema_bundle(schemas: &[ComponentSchema]) -> Value {
+    let actions = trigger_actions(schemas);
+    let concrete_schemas: Vec<&ComponentSchema> = schemas
+        .iter()
+        .filter(|schema| !schema.is_abstract)
+        .collect();
+
+    let definitions: Map<String, Value> = concrete_schemas
+        .iter()
+        .map(|schema| {
+            (
+                schema.type_name.clone(),
+                component_definition(schema, &actions),
+            )
+        }).collect();
+    let references: Vec<Value> = concrete_schemas
+        .iter()
+        .map(|schema| {
+            json!({ "$ref": format!("#/definitions/{}", escape_token(&schema.type_name)) })
+        }).collect();
+
+    json!({
+        "$schema": JSON_SCHEMA_DRAFT,
+        "title": "Component",
+        "description": "Project component of any known type.",
+        "definitions": definitions,
+        "oneOf": references,
+    })
+}
+
+/// Returns sorted unique types of the actions declared by the non-abstract component schemas,
+/// trigger can perform any of them as long as target component supports it.
+fn trigger_actions(schemas: &[ComponentSchema]) -> Vec<&str> {
+    let mut actions: Vec<&str> = schemas
+        .iter()
+        .filter(|schema| !schema.is_abstract)
+        .flat_map(|schema| schema.actions.keys().map(String::as_str))
+        .collect();
+    actions.sort();
+    actions.dedup();
+
+    actions
+}
+
+/// Builds JSON Schema of the component with the specified schema, without `$schema` keyword.
+fn component_definition(schema: &ComponentSchema, actions: &[&str]) -> Value {
+    let properties: Map<String, Value> = schema
+        .properties
+        .iter()
+        .map(|(property_name, property)| (property_name.clone(), property_definition(property)))
+        .collect();
+
+    let mut triggers: Vec<&str> = schema.triggers.keys().map(String::as_str).collect();
+    triggers.sort();
+
+    json!({
+        "title": schema.name,
+        "description": schema.description,
+        "type": "object",
+        "required": ["id", "type", "properties", "triggers"],
+        "properties": {
+            "id": { "type": "string" },
+            "type": { "const": schema.type_name },
+            "schemaVersion": { "type": "integer", "minimum": 1, "maximum": schema.version },
+            "properties": {
+                "type": "object",
+                "properties": properties,
+                "additionalProperties": false,
+            },
+            "triggers": {
+                "type": "object",
+                "propertyNames": string_enum(&triggers),
+                "additionalProperties": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["action", "component"],
+                        "properties": {
+                            "action": string_enum(actions),
+                            "component": { "type": "string" },
+                        },
+                        "additionalProperties": false,
+                    },
+                },
+            },
+        },
+        "additionalProperties": false,
+    })
+}
+
+/// Builds JSON Schema of the component property value.
+fn property_definition(property: &ComponentPropertySchema) -> Value {
+    let mut definition = match property.kind {
+        ComponentPropertyValueKind::Custom | ComponentPropertyValueKind::Component(_) => {
+            json!({ "type": "string" })
+        }
+        ComponentPropertyValueKind::Predefined(ref options) => json!({
+            "type": "string",
+            "oneOf": options
+                .iter()
+                .map(|option| json!({
+                    "const": option.type_name,
+                    "title": option.name,
+                    "description": option.description,
+                })).collect::<Vec<_>>(),
+        }),
+        ComponentPropertyValueKind::Integer(_) => pattern("-?[0-9]+"),
+        ComponentPropertyValueKind::Float(_) => pattern(NUMBER_PATTERN),
+        ComponentPropertyValueKind::Boolean => {
+            json!({ "type": "string", "enum": ["true", "false"] })
+        }
+        ComponentPropertyValueKind::Duration(_) => pattern(&format!("{}ms", NUMBER_PATTERN)),
+        ComponentPropertyValueKind::Color => pattern("#[0-9a-f]{6}"),
+        ComponentPropertyValueKind::Quantity(ref quantity) => {
+            let prefixes: Vec<&str> = SI_PREFIXES
+                .iter()
+                .map(|(prefix, _)| *prefix)
+                .filter(|prefix| !prefix.is_empty())
+                .collect();
+            pattern(&format!(
+                "{}({})?{}",
+                NUMBER_PATTERN,
+                prefixes.join("|"),
+                escape_regex(&quantity.unit)
+            ))
+        }
+    };
+
+    definition["title"] = json!(property.name);
+    definition["description"] = json!(property.description);
+    if !property.default_value.is_empty() {
+        definition["default"] = json!(property
+            .kind
+            .normalize_value(&property.default_value)
+            .unwrap_or_else(|_| property.default_value.clone()));
+    }
+    definition["x-kind"] = serde_json::to_value(&property.kind).unwrap_or_default();
+
+    definition
+}
+
+/// Builds JSON Schema of the string that should fully match the pattern.
+fn pattern(pattern: &str) -> Value {
+    json!({ "type": "string", "pattern": format!("^{}$", pattern) })
+}
+
+/// Builds JSON Schema of the string that should be one of the values. Empty `enum` isn't allowed,
+/// so `false` schema that doesn't match anything is returned if there are no values.
+fn string_enum(values: &[&str]) -> Value {
+    if values.is_empty() {
+        Value::Bool(false)
+    } else {
+        json!({ "type": "string", "enum": values })
+    }
+}
+
+/// Escapes characters that have special meaning in regular expressions.
+fn escape_regex(value: &str) -> String {
+    value.chars().fold(String::new(), |mut escaped, c| {
+        if "\\.^$|?*+()[]{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        escaped
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{component_json_schema, component_json_schema_bundle};
+    use components::component_property_schema::{
+        ComponentPropertyQuantity, ComponentPropertyRange, ComponentPropertySchema,
+        ComponentPropertyValueKind,
+    };
+    use components::component_schema::{ComponentAction, ComponentSchema, ComponentTrigger};
+    use test_fixtures;
+
+    fn schema(type_name: &str) -> ComponentSchema {
+        let mut schema = test_fixtures::schema(type_name);
+        schema.version = 2;
+        schema.name = type_name.to_uppercase();
+        schema
+    }
+
+    fn schemas() -> Vec<ComponentSchema> {
+        let mut led = schema("led");
+        led.properties.insert(
+            "resistance".to_string(),
+            ComponentPropertySchema {
+                type_name: "resistance".to_string(),
+                name: "Resistance".to_string(),
+                description: String::new(),
+                default_value: "220".to_string(),
+                kind: ComponentPropertyValueKind::Quantity(ComponentPropertyQuantity {
+                    unit: "Ω".to_string(),
+                    range: ComponentPropertyRange::default(),
+                }),
+            },
+        );
+        led.actions.insert(
+            "toggle".to_string(),
+            ComponentAction {
+                type_name: "toggle".to_string(),
+                name: "Toggle".to_string(),
+                description: String::new(),
+            },
+        );
+
+        let mut button = schema("button");
+        button.triggers.insert(
+            "pressed".to_string(),
+            ComponentTrigger {
+                type_name: "pressed".to_string(),
+                name: "Pressed".to_string(),
+                description: String::new(),
+            },
+        );
+
+        let mut pressable = schema("pressable");
+        pressable.is_abstract = true;
+
+        vec![led, button, pressable]
+    }
+
+    #[test]
+    fn component_schema_should_be_translated() {
+        let schemas = schemas();
+
+        let led = component_json_schema(&schemas[0], &schemas);
+        assert_eq!(led["$schema"], "http://json-schema.org/draft-07/schema#");
+        assert_eq!(led["properties"]["type"], json!({ "const": "led" }));
+        assert_eq!(led["properties"]["schemaVersion"]["maximum"], 2);
+
+        let resistance = &led["properties"]["properties"]["properties"]["resistance"];
+        assert_eq!(resistance["title"], "Resistance");
+        assert_eq!(resistance["default"], "220Ω");
+        assert_eq!(
+            resistance["pattern"],
+            r"^-?[0-9]+(\.[0-9]+)?(p|n|µ|u|m|k|M|G)?Ω$"
+        );
+        assert_eq!(resistance["x-kind"]["quantity"]["unit"], "Ω");
+
+        // LED doesn't declare any triggers.
+        assert_eq!(led["properties"]["triggers"]["propertyNames"], false);
+
+        let button = component_json_schema(&schemas[1], &schemas);
+        let triggers = &button["properties"]["triggers"];
+        assert_eq!(
+            triggers["propertyNames"],
+            json!({ "type": "string", "enum": ["pressed"] })
+        );
+        assert_eq!(
+            triggers["additionalProperties"]["items"]["properties"]["action"],
+            json!({ "type": "string", "enum": ["toggle"] })
+        );
+    }
+
+    #[test]
+    fn bundle_should_include_only_concrete_schemas() {
+        let bundle = component_json_schema_bundle(&schemas());
+
+        assert!(bundle["definitions"]["led"].is_object());
+        assert!(bundle["definitions"]["button"].is_object());
+        assert!(bundle["definitions"].get("pressable").is_none());
+        assert_eq!(
+            bundle["oneOf"],
+            json!([
+                { "$ref": "#/definitions/led" },
+                { "$ref": "#/definitions/button" },
+            ])
+        );
+    }
+}
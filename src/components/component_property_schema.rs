@@ -1,5 +1,10 @@
+use components::component_property_value::{
+    check_range, format_duration, format_number, format_quantity, parse_boolean, parse_color,
+    parse_duration, parse_float, parse_integer, parse_quantity,
+};
+
 /// Describes component property predefined value.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ComponentPropertyPredefinedValue {
     /// Property value option type.
     #[serde(rename(serialize = "type", deserialize = "type"))]
@@ -10,8 +15,30 @@ pub struct ComponentPropertyPredefinedValue {
     pub description: String,
 }
 
+/// Describes allowed range of the numeric component property value.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ComponentPropertyRange {
+    /// Minimum allowed value (inclusive), if any.
+    pub min: Option<f64>,
+    /// Maximum allowed value (inclusive), if any.
+    pub max: Option<f64>,
+    /// Value should be a multiple of the step counting from the minimum (or `0` if minimum isn't
+    /// set), if any.
+    pub step: Option<f64>,
+}
+
+/// Describes unit-bearing component property value, e.g. resistance or voltage.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ComponentPropertyQuantity {
+    /// Symbol of the base unit, e.g. `Ω` or `V`.
+    pub unit: String,
+    /// Allowed range of the value in base units.
+    #[serde(flatten)]
+    pub range: ComponentPropertyRange,
+}
+
 /// Describes kind of component property.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ComponentPropertyValueKind {
     /// Value is arbitrary string.
     #[serde(rename(serialize = "custom", deserialize = "custom"))]
@@ -23,10 +50,86 @@ pub enum ComponentPropertyValueKind {
     /// component types.
     #[serde(rename(serialize = "component", deserialize = "component"))]
     Component(Vec<String>),
+    /// Value is integer number, e.g. `255`.
+    #[serde(rename(serialize = "integer", deserialize = "integer"))]
+    Integer(ComponentPropertyRange),
+    /// Value is floating point number, e.g. `0.75`.
+    #[serde(rename(serialize = "float", deserialize = "float"))]
+    Float(ComponentPropertyRange),
+    /// Value is either `true` or `false`.
+    #[serde(rename(serialize = "boolean", deserialize = "boolean"))]
+    Boolean,
+    /// Value is duration, e.g. `500ms` or `1.5s`, range is specified in milliseconds.
+    #[serde(rename(serialize = "duration", deserialize = "duration"))]
+    Duration(ComponentPropertyRange),
+    /// Value is hex color, e.g. `#ff0000`.
+    #[serde(rename(serialize = "color", deserialize = "color"))]
+    Color,
+    /// Value is number with optional SI prefix and unit, e.g. `10kΩ` or `3.3V`.
+    #[serde(rename(serialize = "quantity", deserialize = "quantity"))]
+    Quantity(ComponentPropertyQuantity),
+}
+
+impl ComponentPropertyValueKind {
+    /// Returns name of the kind as it's serialized, e.g. `predefined`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ComponentPropertyValueKind::Custom => "custom",
+            ComponentPropertyValueKind::Predefined(_) => "predefined",
+            ComponentPropertyValueKind::Component(_) => "component",
+            ComponentPropertyValueKind::Integer(_) => "integer",
+            ComponentPropertyValueKind::Float(_) => "float",
+            ComponentPropertyValueKind::Boolean => "boolean",
+            ComponentPropertyValueKind::Duration(_) => "duration",
+            ComponentPropertyValueKind::Color => "color",
+            ComponentPropertyValueKind::Quantity(_) => "quantity",
+        }
+    }
+
+    /// Parses value of the typed kind, checks it against the kind constraints and returns its
+    /// canonical representation: integers and floats without redundant signs and zeros, `true`
+    /// or `false` for booleans, milliseconds for durations (`1500ms`), lowercase `#rrggbb` for
+    /// colors and the most compact SI prefix for quantities (`4.7kΩ`). Values of the untyped
+    /// kinds are returned as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Property value to normalize.
+    pub fn normalize_value(&self, value: &str) -> Result<String, String> {
+        match self {
+            ComponentPropertyValueKind::Custom
+            | ComponentPropertyValueKind::Predefined(_)
+            | ComponentPropertyValueKind::Component(_) => Ok(value.to_string()),
+            ComponentPropertyValueKind::Integer(range) => {
+                let number = parse_integer(value)?;
+                check_range(number as f64, range, format_number)?;
+                Ok(number.to_string())
+            }
+            ComponentPropertyValueKind::Float(range) => {
+                let number = parse_float(value)?;
+                check_range(number, range, format_number)?;
+                Ok(format_number(number))
+            }
+            ComponentPropertyValueKind::Boolean => Ok(parse_boolean(value)?.to_string()),
+            ComponentPropertyValueKind::Duration(range) => {
+                let milliseconds = parse_duration(value)?;
+                check_range(milliseconds, range, format_duration)?;
+                Ok(format_duration(milliseconds))
+            }
+            ComponentPropertyValueKind::Color => parse_color(value),
+            ComponentPropertyValueKind::Quantity(quantity) => {
+                let number = parse_quantity(value, &quantity.unit)?;
+                check_range(number, &quantity.range, |number| {
+                    format_quantity(number, &quantity.unit)
+                })?;
+                Ok(format_quantity(number, &quantity.unit))
+            }
+        }
+    }
 }
 
 /// Describes single component property.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ComponentPropertySchema {
     /// Property type.
     #[serde(rename(serialize = "type", deserialize = "type"))]
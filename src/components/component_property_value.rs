@@ -0,0 +1,323 @@
+use components::component_property_schema::ComponentPropertyRange;
+
+/// SI prefixes that unit-bearing values can use, `µ` is canonical for micro, but `u` is accepted
+/// as well since it's easier to type.
+pub const SI_PREFIXES: &[(&str, i32)] = &[
+    ("p", -12),
+    ("n", -9),
+    ("µ", -6),
+    ("u", -6),
+    ("m", -3),
+    ("", 0),
+    ("k", 3),
+    ("M", 6),
+    ("G", 9),
+];
+
+/// Units that durations can use and their length in milliseconds.
+const DURATION_UNITS: &[(&str, f64)] = &[
+    ("µs", 0.001),
+    ("us", 0.001),
+    ("ms", 1.0),
+    ("s", 1000.0),
+    ("min", 60_000.0),
+    ("h", 3_600_000.0),
+];
+
+/// Number of significant digits parsed values are rounded to, so that values like `4.7µ` don't
+/// turn into `4.699999999999999µ` once converted back and forth.
+const SIGNIFICANT_DIGITS: usize = 12;
+
+fn round(value: f64) -> f64 {
+    format!("{:.*e}", SIGNIFICANT_DIGITS - 1, value)
+        .parse()
+        .unwrap_or(value)
+}
+
+/// Formats number with the shortest representation that doesn't use exponent.
+pub fn format_number(value: f64) -> String {
+    // Negative zero would be formatted as `-0`.
+    if value == 0.0 {
+        "0".to_string()
+    } else {
+        round(value).to_string()
+    }
+}
+
+/// Parses integer number, e.g. `-10` or `+255`.
+pub fn parse_integer(value: &str) -> Result<i64, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Value `{}` is not an integer number.", value))
+}
+
+/// Parses finite floating point number, e.g. `3.3` or `-1e-3`.
+pub fn parse_float(value: &str) -> Result<f64, String> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+        .ok_or_else(|| format!("Value `{}` is not a number.", value))
+}
+
+/// Parses boolean value, besides `true` and `false` common synonyms (`yes`/`no`, `on`/`off` and
+/// `1`/`0`) are accepted in any case.
+pub fn parse_boolean(value: &str) -> Result<bool, String> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(format!("Value `{}` is not a boolean.", value)),
+    }
+}
+
+/// Parses duration, e.g. `500ms`, `1.5s` or `2min`, and returns its length in milliseconds.
+/// Numbers without unit are treated as milliseconds.
+pub fn parse_duration(value: &str) -> Result<f64, String> {
+    let invalid_duration = || {
+        format!(
+            "Value `{}` is not a duration, number with one of the units is expected: {}.",
+            value,
+            DURATION_UNITS
+                .iter()
+                .map(|(unit, _)| format!("`{}`", unit))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+
+    let (number, unit) = split_number(value).ok_or_else(invalid_duration)?;
+    let unit_length = if unit.is_empty() {
+        1.0
+    } else {
+        DURATION_UNITS
+            .iter()
+            .find(|(known_unit, _)| *known_unit == unit)
+            .map(|(_, unit_length)| *unit_length)
+            .ok_or_else(invalid_duration)?
+    };
+
+    Ok(round(number * unit_length))
+}
+
+/// Formats duration in milliseconds, e.g. `1500ms`.
+pub fn format_duration(milliseconds: f64) -> String {
+    format!("{}ms", format_number(milliseconds))
+}
+
+/// Parses hex color in either short (`#rgb`) or long (`#rrggbb`) form and returns it in the
+/// canonical lowercase long form.
+pub fn parse_color(value: &str) -> Result<String, String> {
+    let color = value.trim();
+    let digits: Vec<char> = if color.starts_with('#') {
+        color[1..].to_lowercase().chars().collect()
+    } else {
+        vec![]
+    };
+
+    let is_valid = (digits.len() == 3 || digits.len() == 6)
+        && digits.iter().all(|digit| digit.is_ascii_hexdigit());
+    if !is_valid {
+        return Err(format!(
+            "Value `{}` is not a color, `#rgb` or `#rrggbb` is expected.",
+            value
+        ));
+    }
+
+    let repeat = if digits.len() == 3 { 2 } else { 1 };
+    Ok(digits.iter().fold("#".to_string(), |mut color, digit| {
+        for _ in 0..repeat {
+            color.push(*digit);
+        }
+        color
+    }))
+}
+
+/// Parses value with optional SI prefix and unit, e.g. `10kΩ`, `3.3V` or `4.7 uF`, and returns it
+/// in base units. Unit can be omitted (`10k`), but if it's present it should match `unit`.
+pub fn parse_quantity(value: &str, unit: &str) -> Result<f64, String> {
+    let invalid_quantity = || {
+        format!(
+            "Value `{}` is not a number with optional SI prefix and `{}` unit, e.g. `4.7k{}`.",
+            value, unit, unit
+        )
+    };
+
+    let (number, suffix) = split_number(value).ok_or_else(invalid_quantity)?;
+    let prefix = if !unit.is_empty() && suffix.ends_with(unit) {
+        &suffix[..suffix.len() - unit.len()]
+    } else {
+        suffix
+    };
+
+    let exponent = SI_PREFIXES
+        .iter()
+        .find(|(known_prefix, _)| *known_prefix == prefix)
+        .map(|(_, exponent)| *exponent)
+        .ok_or_else(invalid_quantity)?;
+
+    Ok(round(number * 10f64.powi(exponent)))
+}
+
+/// Formats value in base units using SI prefix that keeps number between `1` and `1000`, e.g.
+/// `4700` ohms are formatted as `4.7kΩ`.
+pub fn format_quantity(value: f64, unit: &str) -> String {
+    let magnitude = value.abs();
+    let mut exponent = if magnitude == 0.0 {
+        0
+    } else {
+        ((magnitude.log10() / 3.0).floor() as i32 * 3)
+            .max(-12)
+            .min(9)
+    };
+
+    let mut number = round(value / 10f64.powi(exponent));
+    // Rounding may push number to the next prefix, e.g. `999.9999999999` turns into `1000`.
+    if number.abs() >= 1000.0 && exponent < 9 {
+        exponent += 3;
+        number = round(value / 10f64.powi(exponent));
+    }
+
+    let prefix = SI_PREFIXES
+        .iter()
+        .find(|(_, prefix_exponent)| *prefix_exponent == exponent)
+        .map_or("", |(prefix, _)| *prefix);
+
+    format!("{}{}{}", format_number(number), prefix, unit)
+}
+
+/// Checks that number fits into the range and is a multiple of the range step counting from the
+/// range minimum (or `0` if minimum isn't set).
+///
+/// # Arguments
+///
+/// * `value` - Number to check.
+/// * `range` - Range number should fit into.
+/// * `format` - Function that formats numbers for the error messages.
+pub fn check_range<F>(value: f64, range: &ComponentPropertyRange, format: F) -> Result<(), String>
+where
+    F: Fn(f64) -> String,
+{
+    if let Some(min) = range.min {
+        if value < min {
+            return Err(format!(
+                "Value `{}` is less than minimum `{}`.",
+                format(value),
+                format(min)
+            ));
+        }
+    }
+
+    if let Some(max) = range.max {
+        if value > max {
+            return Err(format!(
+                "Value `{}` is greater than maximum `{}`.",
+                format(value),
+                format(max)
+            ));
+        }
+    }
+
+    if let Some(step) = range.step.filter(|step| *step > 0.0) {
+        let steps = (value - range.min.unwrap_or(0.0)) / step;
+        if (steps - steps.round()).abs() > 1e-6 {
+            return Err(format!(
+                "Value `{}` is not a multiple of step `{}`.",
+                format(value),
+                format(step)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits value into leading decimal number and trimmed suffix that follows it, e.g. `4.7 kΩ`
+/// is split into `4.7` and `kΩ`. Returns `None` if value doesn't start with a number.
+fn split_number(value: &str) -> Option<(f64, &str)> {
+    let value = value.trim();
+    let number_length = value
+        .char_indices()
+        .find(|(index, c)| !(c.is_ascii_digit() || *c == '.' || (*index == 0 && *c == '-')))
+        .map_or(value.len(), |(index, _)| index);
+
+    let number = &value[..number_length];
+    if !number.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    number
+        .parse()
+        .ok()
+        .map(|number| (number, value[number_length..].trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_range, format_duration, format_number, format_quantity, parse_boolean, parse_color,
+        parse_duration, parse_quantity,
+    };
+    use components::component_property_schema::ComponentPropertyRange;
+
+    #[test]
+    fn values_should_be_parsed_and_formatted() {
+        assert_eq!(parse_boolean(" Yes"), Ok(true));
+        assert_eq!(parse_boolean("off"), Ok(false));
+        assert!(parse_boolean("maybe").is_err());
+
+        assert_eq!(parse_color("#F0a"), Ok("#ff00aa".to_string()));
+        assert_eq!(parse_color("#00FF7f"), Ok("#00ff7f".to_string()));
+        assert!(parse_color("00ff7f").is_err());
+        assert!(parse_color("#00ff7").is_err());
+
+        assert_eq!(parse_duration("500"), Ok(500.0));
+        assert_eq!(parse_duration("1.5 s"), Ok(1500.0));
+        assert_eq!(parse_duration("2min"), Ok(120_000.0));
+        assert!(parse_duration("2 weeks").is_err());
+        assert_eq!(format_duration(1500.0), "1500ms");
+
+        assert_eq!(parse_quantity("10kΩ", "Ω"), Ok(10_000.0));
+        assert_eq!(parse_quantity("10k", "Ω"), Ok(10_000.0));
+        assert_eq!(parse_quantity("3.3V", "V"), Ok(3.3));
+        assert_eq!(parse_quantity("4.7 uF", "F"), Ok(0.0000047));
+        assert!(parse_quantity("10kV", "Ω").is_err());
+        assert!(parse_quantity("kΩ", "Ω").is_err());
+
+        assert_eq!(format_quantity(10_000.0, "Ω"), "10kΩ");
+        assert_eq!(format_quantity(3.3, "V"), "3.3V");
+        assert_eq!(format_quantity(0.0000047, "F"), "4.7µF");
+        assert_eq!(format_quantity(0.5, "s"), "500ms");
+        assert_eq!(format_quantity(0.0, "V"), "0V");
+        assert_eq!(
+            format_quantity(parse_quantity("10pF", "F").unwrap(), "F"),
+            "10pF"
+        );
+        assert_eq!(format_number(-0.0), "0");
+    }
+
+    #[test]
+    fn range_should_be_checked() {
+        let range = ComponentPropertyRange {
+            min: Some(10.0),
+            max: Some(100.0),
+            step: Some(0.5),
+        };
+
+        assert!(check_range(10.0, &range, format_number).is_ok());
+        assert!(check_range(55.5, &range, format_number).is_ok());
+        assert_eq!(
+            check_range(5.0, &range, format_number),
+            Err("Value `5` is less than minimum `10`.".to_string())
+        );
+        assert_eq!(
+            check_range(150.0, &range, format_number),
+            Err("Value `150` is greater than maximum `100`.".to_string())
+        );
+        assert_eq!(
+            check_range(10.2, &range, format_number),
+            Err("Value `10.2` is not a multiple of step `0.5`.".to_string())
+        );
+    }
+}
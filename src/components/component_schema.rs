@@ -2,7 +2,7 @@ use components::component_property_schema::ComponentPropertySchema;
 use std::collections::HashMap;
 
 /// Describes component action.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ComponentAction {
     /// Type of the component action.
     #[serde(rename(serialize = "type", deserialize = "type"))]
@@ -14,7 +14,7 @@ pub struct ComponentAction {
 }
 
 /// Describes component trigger.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ComponentTrigger {
     /// Type of the component trigger.
     #[serde(rename(serialize = "type", deserialize = "type"))]
@@ -25,12 +25,20 @@ pub struct ComponentTrigger {
     pub description: String,
 }
 
+fn initial_version() -> i64 {
+    1
+}
+
 /// Describes component properties and actions.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ComponentSchema {
     /// Type of the component that this schema describes.
     #[serde(rename(serialize = "type", deserialize = "type"))]
     pub type_name: String,
+    /// Version of the schema, it's incremented every time schema is updated. Schemas saved before
+    /// versioning was introduced have version `1`.
+    #[serde(default = "initial_version")]
+    pub version: i64,
     /// Human-readable component short name.
     pub name: String,
     /// Human-readable component long description.
@@ -43,4 +51,16 @@ pub struct ComponentSchema {
     pub actions: HashMap<String, ComponentAction>,
     /// Trigger type <-> trigger map.
     pub triggers: HashMap<String, ComponentTrigger>,
+    /// Type of the parent component schema which properties, actions and triggers are inherited,
+    /// if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    /// Types of the component schemas which properties, actions and triggers are mixed in after
+    /// the parent ones, later mixins take precedence over the earlier ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mixins: Vec<String>,
+    /// Indicates whether schema can only be used as a parent or mixin of the other schemas, but
+    /// not by the project components directly.
+    #[serde(default, rename(serialize = "abstract", deserialize = "abstract"))]
+    pub is_abstract: bool,
 }
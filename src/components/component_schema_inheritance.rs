@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::mem;
+
+use components::component_schema::ComponentSchema;
+
+/// Resolves effective versions of all component schemas, see `resolve_schema`. Schemas keep
+/// their order.
+///
+/// # Arguments
+///
+/// * `schemas` - List of all known component schemas as they are stored.
+pub fn resolve_schemas(schemas: &[ComponentSchema]) -> Result<Vec<ComponentSchema>, String> {
+    let schemas_by_type: HashMap<&str, &ComponentSchema> = schemas
+        .iter()
+        .map(|schema| (schema.type_name.as_str(), schema))
+        .collect();
+
+    schemas
+        .iter()
+        .map(|schema| resolve(schema, &schemas_by_type, &mut vec![]))
+        .collect()
+}
+
+/// Resolves effective version of the component schema: properties, actions and triggers of the
+/// parent schema go first, then the ones of the mixins in the order they are listed and then the
+/// schema own ones. Entries with the same type override the preceding ones, but property can't
+/// change the kind of the inherited property. Fails if schema refers to the unknown schema or
+/// inheritance chain has a cycle.
+///
+/// # Arguments
+///
+/// * `schema` - Component schema to resolve.
+/// * `schemas` - List of all known component schemas as they are stored.
+pub fn resolve_schema(
+    schema: &ComponentSchema,
+    schemas: &[ComponentSchema],
+) -> Result<ComponentSchema, String> {
+    let schemas_by_type: HashMap<&str, &ComponentSchema> = schemas
+        .iter()
+        .map(|schema| (schema.type_name.as_str(), schema))
+        .collect();
+
+    resolve(schema, &schemas_by_type, &mut vec![])
+}
+
+/// Returns sorted types of the schemas that use schema with the specified type as a parent or
+/// mixin.
+///
+/// # Arguments
+///
+/// * `type_name` - Type of the component schema.
+/// * `schemas` - List of all known component schemas as they are stored.
+pub fn dependent_schemas(type_name: &str, schemas: &[ComponentSchema]) -> Vec<String> {
+    let mut dependent_types: Vec<String> = schemas
+        .iter()
+        .filter(|schema| base_types(schema).any(|base_type| base_type == type_name))
+        .map(|schema| schema.type_name.clone())
+        .collect();
+    dependent_types.sort();
+
+    dependent_types
+}
+
+/// Returns types of the parent schema and mixins in the order they are applied.
+fn base_types<'a>(schema: &'a ComponentSchema) -> impl Iterator<Item = &'a str> {
+    schema
+        .extends
+        .iter()
+        .chain(schema.mixins.iter())
+        .map(|base_type| base_type.as_str())
+}
+
+/// Resolves schema recursively, `chain` contains types of the schemas that are being resolved and
+/// is used to detect cycles.
+fn resolve<'a>(
+    schema: &'a ComponentSchema,
+    schemas: &HashMap<&str, &'a ComponentSchema>,
+    chain: &mut Vec<&'a str>,
+) -> Result<ComponentSchema, String> {
+    if let Some(index) = chain
+        .iter()
+        .position(|type_name| *type_name == schema.type_name)
+    {
+        let mut cycle = chain[index..].to_vec();
+        cycle.push(&schema.type_name);
+        return Err(format!(
+            "Component schema `{}` inherits from itself: {}.",
+            schema.type_name,
+            cycle.join(" -> ")
+        ));
+    }
+
+    chain.push(&schema.type_name);
+
+    let mut effective_schema = ComponentSchema {
+        properties: HashMap::new(),
+        actions: HashMap::new(),
+        triggers: HashMap::new(),
+        ..schema.clone()
+    };
+
+    for base_type in base_types(schema) {
+        let base_schema = schemas.get(base_type).ok_or_else(|| {
+            format!(
+                "Component schema `{}` refers to unknown schema `{}`.",
+                schema.type_name, base_type
+            )
+        })?;
+
+        let base_schema = resolve(base_schema, schemas, chain)?;
+        effective_schema.properties.extend(base_schema.properties);
+        effective_schema.actions.extend(base_schema.actions);
+        effective_schema.triggers.extend(base_schema.triggers);
+    }
+
+    for (property_name, property) in &schema.properties {
+        if let Some(inherited_property) = effective_schema.properties.get(property_name) {
+            if mem::discriminant(&inherited_property.kind) != mem::discriminant(&property.kind) {
+                return Err(format!(
+                    "Property `{}` of component schema `{}` can't change kind of the inherited \
+                     property from `{}` to `{}`.",
+                    property_name,
+                    schema.type_name,
+                    inherited_property.kind.name(),
+                    property.kind.name()
+                ));
+            }
+        }
+    }
+
+    effective_schema
+        .properties
+        .extend(schema.properties.clone());
+    effective_schema.actions.extend(schema.actions.clone());
+    effective_schema.triggers.extend(schema.triggers.clone());
+
+    chain.pop();
+
+    Ok(effective_schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dependent_schemas, resolve_schema, resolve_schemas};
+    use components::component_property_schema::{
+        ComponentPropertySchema, ComponentPropertyValueKind,
+    };
+    use components::component_schema::{ComponentSchema, ComponentTrigger};
+    use test_fixtures;
+
+    fn schema(type_name: &str, extends: Option<&str>, mixins: &[&str]) -> ComponentSchema {
+        let mut schema = test_fixtures::schema(type_name);
+        schema.extends = extends.map(|extends| extends.to_string());
+        schema.mixins = mixins.iter().map(|mixin| mixin.to_string()).collect();
+        schema
+    }
+
+    fn add_property(schema: &mut ComponentSchema, name: &str, kind: ComponentPropertyValueKind) {
+        schema.properties.insert(
+            name.to_string(),
+            ComponentPropertySchema {
+                type_name: name.to_string(),
+                name: schema.type_name.clone(),
+                description: String::new(),
+                default_value: String::new(),
+                kind,
+            },
+        );
+    }
+
+    #[test]
+    fn schemas_should_be_merged() {
+        let mut pressable = schema("pressable", None, &[]);
+        pressable.is_abstract = true;
+        pressable.triggers.insert(
+            "pressed".to_string(),
+            ComponentTrigger {
+                type_name: "pressed".to_string(),
+                name: "Pressed".to_string(),
+                description: String::new(),
+            },
+        );
+
+        let mut switch = schema("switch", None, &[]);
+        add_property(&mut switch, "label", ComponentPropertyValueKind::Custom);
+        add_property(&mut switch, "pins", ComponentPropertyValueKind::Custom);
+
+        let mut button = schema("button", Some("switch"), &["pressable"]);
+        add_property(&mut button, "label", ComponentPropertyValueKind::Custom);
+
+        let schemas = vec![pressable, switch, button];
+        let button = resolve_schema(&schemas[2], &schemas).unwrap();
+
+        assert_eq!(button.type_name, "button");
+        assert_eq!(button.extends, Some("switch".to_string()));
+        assert_eq!(button.is_abstract, false);
+        assert!(button.triggers.contains_key("pressed"));
+        assert_eq!(button.properties.len(), 2);
+        assert_eq!(button.properties["label"].name, "button");
+        assert_eq!(button.properties["pins"].name, "switch");
+
+        assert_eq!(resolve_schemas(&schemas).unwrap().len(), 3);
+        assert_eq!(dependent_schemas("pressable", &schemas), vec!["button"]);
+    }
+
+    #[test]
+    fn invalid_inheritance_should_be_reported() {
+        let schemas = vec![
+            schema("a", Some("b"), &[]),
+            schema("b", None, &["c"]),
+            schema("c", Some("a"), &[]),
+        ];
+        assert_eq!(
+            resolve_schemas(&schemas).unwrap_err(),
+            "Component schema `a` inherits from itself: a -> b -> c -> a."
+        );
+
+        let schemas = vec![schema("a", None, &["unknown"])];
+        assert_eq!(
+            resolve_schemas(&schemas).unwrap_err(),
+            "Component schema `a` refers to unknown schema `unknown`."
+        );
+
+        let mut parent = schema("parent", None, &[]);
+        add_property(&mut parent, "size", ComponentPropertyValueKind::Custom);
+        let mut child = schema("child", Some("parent"), &[]);
+        add_property(&mut child, "size", ComponentPropertyValueKind::Boolean);
+        assert_eq!(
+            resolve_schemas(&[parent, child]).unwrap_err(),
+            "Property `size` of component schema `child` can't change kind of the inherited \
+             property from `custom` to `boolean`."
+        );
+    }
+}
@@ -1,4 +1,7 @@
 pub mod component;
 pub mod component_group;
+pub mod component_json_schema;
 pub mod component_property_schema;
+pub mod component_property_value;
 pub mod component_schema;
+pub mod component_schema_inheritance;
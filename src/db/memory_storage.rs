@@ -0,0 +1,1022 @@
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use failure::{err_msg, Error};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{self, Value};
+use uuid::Uuid;
+
+use super::migrations::{AppliedMigration, MIGRATIONS};
+use super::storage::{read_documents, Page, ReadFailure, ReadMode, ReplaceResult, Storage};
+use components::component_group::ComponentGroup;
+use components::component_schema::ComponentSchema;
+use projects::project::Project;
+use projects::project_capability::ProjectCapability;
+use projects::project_capability_group::ProjectCapabilityGroup;
+use projects::project_platform::ProjectPlatform;
+use projects::project_query::{ProjectQuery, ProjectSortField};
+use projects::project_revision::{ProjectChange, ProjectRevision};
+use projects::project_search::search;
+use projects::project_template::ProjectTemplate;
+use projects::trashed_project::TrashedProject;
+use utils::current_timestamp;
+
+/// All collections kept by the in-memory storage. Catalogs are kept as JSON documents, so that
+/// catalog items that can't be read are skipped and recorded the same way database does it.
+#[derive(Default)]
+struct Collections {
+    projects: Vec<Project>,
+    project_revisions: Vec<ProjectRevision>,
+    trashed_projects: Vec<TrashedProject>,
+    component_groups: Vec<Value>,
+    component_schemas: Vec<Value>,
+    component_schema_versions: Vec<Value>,
+    project_capabilities: Vec<Value>,
+    project_capability_groups: Vec<Value>,
+    project_platforms: Vec<Value>,
+    project_templates: Vec<Value>,
+    applied_migrations: Vec<AppliedMigration>,
+    read_failures: Vec<ReadFailure>,
+}
+
+/// Storage that keeps all the data in memory, it's used for tests and local demos that should
+/// work without running database. Cloned instances share the same data.
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    collections: Arc<RwLock<Collections>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        MemoryStorage::default()
+    }
+
+    fn read(&self) -> Result<RwLockReadGuard<Collections>, Error> {
+        self.collections
+            .read()
+            .map_err(|_| err_msg("In-memory storage lock is poisoned."))
+    }
+
+    fn write(&self) -> Result<RwLockWriteGuard<Collections>, Error> {
+        self.collections
+            .write()
+            .map_err(|_| err_msg("In-memory storage lock is poisoned."))
+    }
+
+    /// Deserializes catalog documents, documents that can't be read are recorded as read failures
+    /// and either skipped or returned as an error depending on the `read_mode`.
+    fn read_catalog<T>(
+        &self,
+        collection_name: &str,
+        documents: Vec<Value>,
+        read_mode: ReadMode,
+    ) -> Result<Vec<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let mut failures = vec![];
+        let items = read_documents(
+            collection_name,
+            documents.into_iter().map(|document| {
+                (
+                    document_id(&document),
+                    serde_json::from_value(document).map_err(Error::from),
+                )
+            }),
+            read_mode,
+            |failure| failures.push(failure),
+        );
+
+        if !failures.is_empty() {
+            let mut collections = self.write()?;
+            for failure in failures {
+                upsert(&mut collections.read_failures, failure, |failure| {
+                    (failure.collection.clone(), failure.document_id.clone())
+                });
+            }
+        }
+
+        items
+    }
+}
+
+/// Returns value of the string field of the document, if any.
+fn document_field(document: &Value, field: &str) -> Option<String> {
+    document
+        .get(field)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Checks whether string field of the document has the specified value.
+fn has_field(document: &Value, field: &str, value: &str) -> bool {
+    document.get(field).and_then(Value::as_str) == Some(value)
+}
+
+/// Returns copies of the documents which string field has the specified value.
+fn filter_documents(documents: &[Value], field: &str, value: &str) -> Vec<Value> {
+    documents
+        .iter()
+        .filter(|document| has_field(document, field, value))
+        .cloned()
+        .collect()
+}
+
+/// Returns identifier of the catalog document: template identifier or catalog item type.
+fn document_id(document: &Value) -> Option<String> {
+    document_field(document, "id").or_else(|| document_field(document, "type"))
+}
+
+/// Serializes item into the document and saves it, replacing the document that has the same value
+/// of the `key_field`.
+fn save_document<T>(documents: &mut Vec<Value>, item: &T, key_field: &str) -> Result<(), Error>
+where
+    T: Serialize,
+{
+    upsert(documents, serde_json::to_value(item)?, |document| {
+        document_field(document, key_field)
+    });
+    Ok(())
+}
+
+/// Identifies one of the previous component schema versions by schema type and version.
+fn schema_version_key(schema: &Value) -> (Option<String>, Option<Value>) {
+    (
+        document_field(schema, "type"),
+        schema.get("version").cloned(),
+    )
+}
+
+/// Replaces item that has the same key as `item` or appends `item` if there is no such item.
+fn upsert<T, K, F>(items: &mut Vec<T>, item: T, key: F)
+where
+    K: PartialEq,
+    F: Fn(&T) -> K,
+{
+    let item_key = key(&item);
+    match items
+        .iter_mut()
+        .find(|existing_item| key(existing_item) == item_key)
+    {
+        Some(existing_item) => *existing_item = item,
+        None => items.push(item),
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn get_project(&self, project_id: &str) -> Result<Option<Project>, Error> {
+        Ok(self
+            .read()?
+            .projects
+            .iter()
+            .find(|project| project.id == project_id)
+            .cloned())
+    }
+
+    fn trash_project(&self, project_id: &str, change: ProjectChange) -> Result<bool, Error> {
+        let mut collections = self.write()?;
+        let position = collections
+            .projects
+            .iter()
+            .position(|project| project.id == project_id);
+
+        Ok(if let Some(position) = position {
+            let project = collections.projects.remove(position);
+            collections.trashed_projects.push(TrashedProject {
+                project,
+                deleted_at: current_timestamp(),
+                deleted_by: change.author,
+            });
+            true
+        } else {
+            false
+        })
+    }
+
+    fn get_trashed_projects(&self, _read_mode: ReadMode) -> Result<Vec<TrashedProject>, Error> {
+        let mut trashed_projects = self.read()?.trashed_projects.clone();
+        trashed_projects
+            .sort_by(|project_a, project_b| project_b.deleted_at.cmp(&project_a.deleted_at));
+
+        Ok(trashed_projects)
+    }
+
+    fn restore_trashed_project(&self, project_id: &str) -> Result<Option<Project>, Error> {
+        let mut collections = self.write()?;
+        let position = collections
+            .trashed_projects
+            .iter()
+            .position(|trashed_project| trashed_project.project.id == project_id);
+
+        Ok(if let Some(position) = position {
+            let project = collections.trashed_projects.remove(position).project;
+            collections.projects.push(project.clone());
+            Some(project)
+        } else {
+            None
+        })
+    }
+
+    fn purge_trashed_project(&self, project_id: &str) -> Result<bool, Error> {
+        let mut collections = self.write()?;
+        let trashed_projects_count = collections.trashed_projects.len();
+        collections
+            .trashed_projects
+            .retain(|trashed_project| trashed_project.project.id != project_id);
+        if collections.trashed_projects.len() == trashed_projects_count {
+            return Ok(false);
+        }
+
+        collections
+            .project_revisions
+            .retain(|revision| revision.project_id != project_id);
+        Ok(true)
+    }
+
+    fn purge_trashed_projects(&self, deleted_before: i64) -> Result<Vec<String>, Error> {
+        let mut collections = self.write()?;
+        let (expired_projects, trashed_projects): (Vec<TrashedProject>, Vec<TrashedProject>) =
+            collections
+                .trashed_projects
+                .drain(..)
+                .partition(|trashed_project| trashed_project.deleted_at < deleted_before);
+        collections.trashed_projects = trashed_projects;
+
+        let purged_project_ids: Vec<String> = expired_projects
+            .into_iter()
+            .map(|trashed_project| trashed_project.project.id)
+            .collect();
+        collections
+            .project_revisions
+            .retain(|revision| !purged_project_ids.contains(&revision.project_id));
+
+        Ok(purged_project_ids)
+    }
+
+    fn get_projects(&self, _read_mode: ReadMode) -> Result<Vec<Project>, Error> {
+        Ok(self.read()?.projects.clone())
+    }
+
+    fn search_projects(&self, query: &str, _read_mode: ReadMode) -> Result<Vec<Project>, Error> {
+        let collections = self.read()?;
+        let matched_ids: Vec<String> = search(&collections.projects, query)
+            .into_iter()
+            .map(|result| result.project.id)
+            .collect();
+
+        Ok(collections
+            .projects
+            .iter()
+            .filter(|project| matched_ids.contains(&project.id))
+            .cloned()
+            .collect())
+    }
+
+    fn find_projects(
+        &self,
+        query: &ProjectQuery,
+        _read_mode: ReadMode,
+    ) -> Result<Page<Project>, Error> {
+        let mut projects: Vec<Project> = self
+            .read()?
+            .projects
+            .iter()
+            .filter(|project| {
+                query
+                    .platform
+                    .as_ref()
+                    .map_or(true, |platform| &project.platform == platform)
+                    && query
+                        .capabilities
+                        .iter()
+                        .all(|capability| project.capabilities.contains(capability))
+            }).cloned()
+            .collect();
+
+        projects.sort_by(|project_a, project_b| {
+            let ordering = match query.sort_field {
+                ProjectSortField::Name => project_a.name.cmp(&project_b.name),
+                ProjectSortField::CreatedAt => project_a.created_at.cmp(&project_b.created_at),
+                ProjectSortField::UpdatedAt => project_a.updated_at.cmp(&project_b.updated_at),
+            };
+
+            let ordering = if query.sort_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            };
+
+            ordering.then_with(|| project_a.id.cmp(&project_b.id))
+        });
+
+        let total = projects.len() as u64;
+        let items = projects
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit)
+            .map(|mut project| {
+                if !query.include_components {
+                    project.components.clear();
+                }
+                project
+            }).collect();
+
+        Ok(Page { items, total })
+    }
+
+    fn insert_project(
+        &self,
+        mut project: Project,
+        change: ProjectChange,
+    ) -> Result<Option<Project>, Error> {
+        if project.id.is_empty() {
+            project.id = Uuid::new_v4().to_string();
+        }
+        project.revision = 1;
+        project.track_creation(change.author, current_timestamp());
+
+        let mut collections = self.write()?;
+        if collections
+            .projects
+            .iter()
+            .any(|existing_project| existing_project.id == project.id)
+        {
+            return Ok(None);
+        }
+
+        collections
+            .project_revisions
+            .push(ProjectRevision::new(&project, change.message));
+        collections.projects.push(project.clone());
+
+        Ok(Some(project))
+    }
+
+    fn replace_project(
+        &self,
+        mut project: Project,
+        expected_revision: i64,
+        change: ProjectChange,
+    ) -> Result<ReplaceResult<Project>, Error> {
+        let mut collections = self.write()?;
+        let result = match collections
+            .projects
+            .iter_mut()
+            .find(|existing_project| existing_project.id == project.id)
+        {
+            Some(ref existing_project) if existing_project.revision != expected_revision => {
+                ReplaceResult::Conflict(existing_project.revision)
+            }
+            Some(existing_project) => {
+                project.revision = expected_revision + 1;
+                project.track_update(existing_project, change.author, current_timestamp());
+                *existing_project = project.clone();
+                ReplaceResult::Replaced(project)
+            }
+            None => ReplaceResult::NotFound,
+        };
+
+        if let ReplaceResult::Replaced(ref project) = result {
+            collections
+                .project_revisions
+                .push(ProjectRevision::new(project, change.message));
+        }
+
+        Ok(result)
+    }
+
+    fn get_project_revisions(
+        &self,
+        project_id: &str,
+        _read_mode: ReadMode,
+    ) -> Result<Vec<ProjectRevision>, Error> {
+        let mut revisions: Vec<ProjectRevision> = self
+            .read()?
+            .project_revisions
+            .iter()
+            .filter(|revision| revision.project_id == project_id)
+            .cloned()
+            .collect();
+        revisions.sort_by(|revision_a, revision_b| revision_b.revision.cmp(&revision_a.revision));
+
+        Ok(revisions)
+    }
+
+    fn get_project_revision(
+        &self,
+        project_id: &str,
+        revision: i64,
+    ) -> Result<Option<ProjectRevision>, Error> {
+        Ok(self
+            .read()?
+            .project_revisions
+            .iter()
+            .find(|project_revision| {
+                project_revision.project_id == project_id && project_revision.revision == revision
+            }).cloned())
+    }
+
+    fn get_component_groups(&self, read_mode: ReadMode) -> Result<Vec<ComponentGroup>, Error> {
+        let documents = self.read()?.component_groups.clone();
+        self.read_catalog("component_groups", documents, read_mode)
+    }
+
+    fn get_component_schemas(&self, read_mode: ReadMode) -> Result<Vec<ComponentSchema>, Error> {
+        let documents = self.read()?.component_schemas.clone();
+        self.read_catalog("component_schemas", documents, read_mode)
+    }
+
+    fn save_component_group(&self, group: ComponentGroup) -> Result<(), Error> {
+        save_document(&mut self.write()?.component_groups, &group, "type")
+    }
+
+    fn get_component_schema_versions(
+        &self,
+        type_name: &str,
+        read_mode: ReadMode,
+    ) -> Result<Vec<ComponentSchema>, Error> {
+        let (documents, previous_documents) = {
+            let collections = self.read()?;
+            (
+                filter_documents(&collections.component_schemas, "type", type_name),
+                filter_documents(&collections.component_schema_versions, "type", type_name),
+            )
+        };
+
+        let mut schemas: Vec<ComponentSchema> =
+            self.read_catalog("component_schemas", documents, read_mode)?;
+        schemas.extend(self.read_catalog::<ComponentSchema>(
+            "component_schema_versions",
+            previous_documents,
+            read_mode,
+        )?);
+        schemas.sort_by(|schema_a, schema_b| schema_b.version.cmp(&schema_a.version));
+
+        Ok(schemas)
+    }
+
+    fn get_component_schema_version(
+        &self,
+        type_name: &str,
+        version: i64,
+    ) -> Result<Option<ComponentSchema>, Error> {
+        Ok(self
+            .get_component_schema_versions(type_name, ReadMode::Strict)?
+            .into_iter()
+            .find(|schema| schema.version == version))
+    }
+
+    fn save_component_schema(
+        &self,
+        schema: ComponentSchema,
+        expected_version: Option<i64>,
+    ) -> Result<ReplaceResult<ComponentSchema>, Error> {
+        let mut collections = self.write()?;
+        let current_schema = collections
+            .component_schemas
+            .iter()
+            .find(|existing_schema| has_field(existing_schema, "type", &schema.type_name))
+            .cloned();
+        let current_version = current_schema
+            .as_ref()
+            .map(|current_schema| current_schema.get("version").and_then(Value::as_i64));
+
+        match (current_version, expected_version) {
+            (Some(current_version), Some(expected_version))
+                if current_version == Some(expected_version) => {}
+            (Some(current_version), _) => {
+                return Ok(ReplaceResult::Conflict(current_version.unwrap_or_default()))
+            }
+            (None, Some(_)) => return Ok(ReplaceResult::NotFound),
+            (None, None) => {}
+        }
+
+        if let Some(current_schema) = current_schema {
+            if current_version != Some(Some(schema.version)) {
+                upsert(
+                    &mut collections.component_schema_versions,
+                    current_schema,
+                    schema_version_key,
+                );
+            }
+        }
+
+        save_document(&mut collections.component_schemas, &schema, "type")?;
+
+        Ok(ReplaceResult::Replaced(schema))
+    }
+
+    fn save_component_schema_version(&self, schema: ComponentSchema) -> Result<(), Error> {
+        upsert(
+            &mut self.write()?.component_schema_versions,
+            serde_json::to_value(schema)?,
+            schema_version_key,
+        );
+
+        Ok(())
+    }
+
+    fn delete_component_group(&self, type_name: &str) -> Result<bool, Error> {
+        let mut collections = self.write()?;
+        let groups_count = collections.component_groups.len();
+        collections
+            .component_groups
+            .retain(|group| !has_field(group, "type", type_name));
+        Ok(collections.component_groups.len() < groups_count)
+    }
+
+    fn delete_component_schema(&self, type_name: &str) -> Result<bool, Error> {
+        let mut collections = self.write()?;
+        let schemas_count = collections.component_schemas.len();
+        collections
+            .component_schemas
+            .retain(|schema| !has_field(schema, "type", type_name));
+        collections
+            .component_schema_versions
+            .retain(|schema| !has_field(schema, "type", type_name));
+        Ok(collections.component_schemas.len() < schemas_count)
+    }
+
+    fn get_component_type_usages(&self, type_name: &str) -> Result<Vec<String>, Error> {
+        let collections = self.read()?;
+        let mut project_ids: Vec<String> = collections
+            .projects
+            .iter()
+            .chain(
+                collections
+                    .trashed_projects
+                    .iter()
+                    .map(|trashed_project| &trashed_project.project),
+            ).filter(|project| {
+                project
+                    .components
+                    .iter()
+                    .any(|component| component.type_name == type_name)
+            }).map(|project| project.id.clone())
+            .collect();
+        project_ids.sort();
+
+        Ok(project_ids)
+    }
+
+    fn get_project_capability_groups(
+        &self,
+        read_mode: ReadMode,
+    ) -> Result<Vec<ProjectCapabilityGroup>, Error> {
+        let documents = self.read()?.project_capability_groups.clone();
+        self.read_catalog("project_capability_groups", documents, read_mode)
+    }
+
+    fn get_project_capabilities(
+        &self,
+        read_mode: ReadMode,
+    ) -> Result<Vec<ProjectCapability>, Error> {
+        let documents = self.read()?.project_capabilities.clone();
+        self.read_catalog("project_capabilities", documents, read_mode)
+    }
+
+    fn get_project_platforms(&self, read_mode: ReadMode) -> Result<Vec<ProjectPlatform>, Error> {
+        let documents = self.read()?.project_platforms.clone();
+        self.read_catalog("project_platforms", documents, read_mode)
+    }
+
+    fn save_project_capability_group(&self, group: ProjectCapabilityGroup) -> Result<(), Error> {
+        save_document(&mut self.write()?.project_capability_groups, &group, "type")
+    }
+
+    fn save_project_capability(&self, capability: ProjectCapability) -> Result<(), Error> {
+        save_document(&mut self.write()?.project_capabilities, &capability, "type")
+    }
+
+    fn save_project_platform(&self, platform: ProjectPlatform) -> Result<(), Error> {
+        save_document(&mut self.write()?.project_platforms, &platform, "type")
+    }
+
+    fn get_project_templates(&self, read_mode: ReadMode) -> Result<Vec<ProjectTemplate>, Error> {
+        let documents = self.read()?.project_templates.clone();
+        self.read_catalog("project_templates", documents, read_mode)
+    }
+
+    fn get_project_template(&self, template_id: &str) -> Result<Option<ProjectTemplate>, Error> {
+        let documents = filter_documents(&self.read()?.project_templates, "id", template_id);
+        Ok(self
+            .read_catalog("project_templates", documents, ReadMode::Strict)?
+            .pop())
+    }
+
+    fn save_project_template(&self, template: ProjectTemplate) -> Result<(), Error> {
+        save_document(&mut self.write()?.project_templates, &template, "id")
+    }
+
+    fn migrate(&self) -> Result<Vec<AppliedMigration>, Error> {
+        // In-memory storage doesn't outlive the process and hence always keeps documents of the
+        // current version, migrations are only recorded.
+        let mut collections = self.write()?;
+        let pending_migrations: Vec<AppliedMigration> = MIGRATIONS
+            .iter()
+            .filter(|migration| {
+                !collections
+                    .applied_migrations
+                    .iter()
+                    .any(|applied_migration| applied_migration.name == migration.name)
+            }).map(|migration| AppliedMigration {
+                kind: migration.kind,
+                version: migration.version,
+                name: migration.name.to_string(),
+                applied_at: current_timestamp(),
+                documents: 0,
+            }).collect();
+
+        collections
+            .applied_migrations
+            .extend(pending_migrations.iter().cloned());
+
+        Ok(pending_migrations)
+    }
+
+    fn get_read_failures(&self) -> Result<Vec<ReadFailure>, Error> {
+        let mut read_failures = self.read()?.read_failures.clone();
+        read_failures
+            .sort_by(|failure_a, failure_b| failure_b.occurred_at.cmp(&failure_a.occurred_at));
+
+        Ok(read_failures)
+    }
+
+    fn clear_read_failures(&self) -> Result<(), Error> {
+        self.write()?.read_failures.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MemoryStorage;
+    use db::storage::{ReadMode, ReplaceResult, Storage};
+    use projects::project_platform::ProjectPlatform;
+    use projects::project_query::{ProjectQuery, ProjectSortField};
+    use projects::project_revision::ProjectChange;
+    use test_fixtures::{component, project, schema};
+
+    fn change(author: &str) -> ProjectChange {
+        ProjectChange {
+            author: Some(author.to_string()),
+            message: None,
+        }
+    }
+
+    #[test]
+    fn projects_should_be_inserted_and_replaced() {
+        let storage = MemoryStorage::new();
+
+        let saved_project = storage
+            .insert_project(project("", "one", vec![]), change("alice"))
+            .unwrap()
+            .unwrap();
+        assert!(!saved_project.id.is_empty());
+        assert_eq!(saved_project.revision, 1);
+        assert!(saved_project.created_at > 0);
+        assert_eq!(saved_project.created_at, saved_project.updated_at);
+        assert_eq!(saved_project.created_by, Some("alice".to_string()));
+        assert_eq!(saved_project.updated_by, Some("alice".to_string()));
+        assert_eq!(storage.get_projects(ReadMode::Strict).unwrap().len(), 1);
+
+        // Project body can't override timestamps and authorship.
+        let mut project_to_save = project(&saved_project.id, "two", vec![]);
+        project_to_save.created_by = Some("mallory".to_string());
+        match storage
+            .replace_project(project_to_save, 1, change("bob"))
+            .unwrap()
+        {
+            ReplaceResult::Replaced(replaced_project) => {
+                assert_eq!(replaced_project.name, "two");
+                assert_eq!(replaced_project.revision, 2);
+                assert_eq!(replaced_project.created_at, saved_project.created_at);
+                assert!(replaced_project.updated_at >= saved_project.updated_at);
+                assert_eq!(replaced_project.created_by, Some("alice".to_string()));
+                assert_eq!(replaced_project.updated_by, Some("bob".to_string()));
+            }
+            result => panic!("Unexpected replace result {:?}", result),
+        }
+
+        let projects = storage.get_projects(ReadMode::Strict).unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name, "two");
+
+        match storage
+            .replace_project(
+                project(&saved_project.id, "three", vec![]),
+                1,
+                change("bob"),
+            )
+            .unwrap()
+        {
+            ReplaceResult::Conflict(current_revision) => assert_eq!(current_revision, 2),
+            result => panic!("Unexpected replace result {:?}", result),
+        }
+
+        match storage
+            .replace_project(project("unknown", "three", vec![]), 1, change("bob"))
+            .unwrap()
+        {
+            ReplaceResult::NotFound => {}
+            result => panic!("Unexpected replace result {:?}", result),
+        }
+
+        let revisions = storage
+            .get_project_revisions(&saved_project.id, ReadMode::Strict)
+            .unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0].revision, 2);
+        assert_eq!(revisions[0].project.name, "two");
+        assert_eq!(revisions[0].author, Some("bob".to_string()));
+        assert_eq!(revisions[1].revision, 1);
+        assert_eq!(revisions[1].project.name, "one");
+
+        // Identifier is kept if it's provided (e.g. for imported projects).
+        let imported_project = storage
+            .insert_project(project("imported", "four", vec![]), change("alice"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(imported_project.id, "imported");
+        assert_eq!(imported_project.revision, 1);
+
+        // Identifier of the existing project can't be reused.
+        assert!(storage
+            .insert_project(project("imported", "five", vec![]), change("bob"))
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            storage.get_project("imported").unwrap().unwrap().name,
+            "four"
+        );
+    }
+
+    #[test]
+    fn projects_should_be_filtered_sorted_and_paginated() {
+        let storage = MemoryStorage::new();
+        for (name, platform, capabilities) in &[
+            ("b", "arduino", vec!["wifi", "usb"]),
+            ("a", "arduino", vec!["wifi"]),
+            ("c", "arduino", vec!["usb"]),
+            ("d", "raspberry", vec!["wifi", "usb"]),
+        ] {
+            let mut project = project("", name, vec![]);
+            project.platform = platform.to_string();
+            project.capabilities = capabilities.iter().map(|c| c.to_string()).collect();
+            storage.insert_project(project, change("alice")).unwrap();
+        }
+
+        let mut query = ProjectQuery::default();
+        query.platform = Some("arduino".to_string());
+        query.capabilities = vec!["usb".to_string()];
+        query.sort_field = ProjectSortField::Name;
+        query.sort_descending = true;
+        query.limit = 1;
+        query.include_components = false;
+
+        let page = storage.find_projects(&query, ReadMode::Strict).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].name, "c");
+
+        query.offset = 1;
+        let page = storage.find_projects(&query, ReadMode::Strict).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items[0].name, "b");
+
+        query.offset = 2;
+        assert!(storage
+            .find_projects(&query, ReadMode::Strict)
+            .unwrap()
+            .items
+            .is_empty());
+    }
+
+    #[test]
+    fn only_matching_projects_should_be_searched() {
+        let storage = MemoryStorage::new();
+        for (name, components) in vec![
+            ("Led strip", vec![]),
+            (
+                "Weather",
+                vec![component("led-1", "led", &[("color", "red")])],
+            ),
+            ("Door bell", vec![]),
+        ] {
+            storage
+                .insert_project(project("", name, components), change("alice"))
+                .unwrap();
+        }
+
+        let mut names: Vec<String> = storage
+            .search_projects("led", ReadMode::Strict)
+            .unwrap()
+            .into_iter()
+            .map(|project| project.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["Led strip", "Weather"]);
+
+        assert_eq!(
+            storage
+                .search_projects("red led", ReadMode::Strict)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn clones_should_share_data() {
+        let storage = MemoryStorage::new();
+        let saved_project = storage
+            .clone()
+            .insert_project(project("", "one", vec![]), ProjectChange::default())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            storage
+                .get_project(&saved_project.id)
+                .unwrap()
+                .unwrap()
+                .name,
+            "one"
+        );
+
+        assert!(storage
+            .trash_project(&saved_project.id, change("alice"))
+            .unwrap());
+        assert!(storage.get_project(&saved_project.id).unwrap().is_none());
+        assert!(!storage
+            .trash_project(&saved_project.id, change("alice"))
+            .unwrap());
+    }
+
+    #[test]
+    fn trashed_projects_should_be_restored_and_purged() {
+        let storage = MemoryStorage::new();
+        let project_one = storage
+            .insert_project(project("", "one", vec![]), change("alice"))
+            .unwrap()
+            .unwrap();
+        let project_two = storage
+            .insert_project(project("", "two", vec![]), change("alice"))
+            .unwrap()
+            .unwrap();
+
+        assert!(storage
+            .trash_project(&project_one.id, change("bob"))
+            .unwrap());
+        assert!(storage
+            .trash_project(&project_two.id, change("bob"))
+            .unwrap());
+        assert!(storage.get_projects(ReadMode::Strict).unwrap().is_empty());
+
+        let trashed_projects = storage.get_trashed_projects(ReadMode::Strict).unwrap();
+        assert_eq!(trashed_projects.len(), 2);
+        assert_eq!(trashed_projects[0].deleted_by, Some("bob".to_string()));
+
+        let restored_project = storage
+            .restore_trashed_project(&project_one.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(restored_project.name, "one");
+        assert!(storage.get_project(&project_one.id).unwrap().is_some());
+        assert!(storage
+            .restore_trashed_project(&project_one.id)
+            .unwrap()
+            .is_none());
+
+        // Revisions are kept while project is in the trash.
+        assert_eq!(
+            storage
+                .get_project_revisions(&project_two.id, ReadMode::Strict)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(storage.purge_trashed_projects(0).unwrap().is_empty());
+        assert_eq!(
+            storage.purge_trashed_projects(i64::max_value()).unwrap(),
+            vec![project_two.id.clone()]
+        );
+        assert!(storage
+            .get_trashed_projects(ReadMode::Strict)
+            .unwrap()
+            .is_empty());
+        assert!(storage
+            .get_project_revisions(&project_two.id, ReadMode::Strict)
+            .unwrap()
+            .is_empty());
+        assert!(!storage.purge_trashed_project(&project_two.id).unwrap());
+    }
+
+    #[test]
+    fn previous_component_schema_versions_should_be_kept() {
+        let storage = MemoryStorage::new();
+        let schema = |version: i64, name: &str| {
+            let mut schema = schema("led");
+            schema.version = version;
+            schema.name = name.to_string();
+            schema
+        };
+
+        storage
+            .save_component_schema(schema(1, "LED"), None)
+            .unwrap();
+        storage
+            .save_component_schema(schema(2, "Led"), Some(1))
+            .unwrap();
+        // Saving the same version again replaces it without keeping a copy.
+        storage
+            .save_component_schema(schema(2, "Light"), Some(2))
+            .unwrap();
+
+        // Schema that has been modified concurrently or already exists isn't saved.
+        match storage
+            .save_component_schema(schema(2, "Lamp"), Some(1))
+            .unwrap()
+        {
+            ReplaceResult::Conflict(current_version) => assert_eq!(current_version, 2),
+            result => panic!("Unexpected save result {:?}", result),
+        }
+        match storage
+            .save_component_schema(schema(1, "Lamp"), None)
+            .unwrap()
+        {
+            ReplaceResult::Conflict(current_version) => assert_eq!(current_version, 2),
+            result => panic!("Unexpected save result {:?}", result),
+        }
+
+        assert_eq!(
+            storage
+                .get_component_schemas(ReadMode::Strict)
+                .unwrap()
+                .len(),
+            1
+        );
+        let versions = storage
+            .get_component_schema_versions("led", ReadMode::Strict)
+            .unwrap();
+        assert_eq!(
+            versions
+                .iter()
+                .map(|schema| (schema.version, schema.name.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(2, "Light"), (1, "LED")]
+        );
+        assert_eq!(
+            storage
+                .get_component_schema_version("led", 1)
+                .unwrap()
+                .unwrap()
+                .name,
+            "LED"
+        );
+        assert!(storage
+            .get_component_schema_version("led", 3)
+            .unwrap()
+            .is_none());
+
+        assert!(storage.delete_component_schema("led").unwrap());
+        assert!(storage
+            .get_component_schema_versions("led", ReadMode::Strict)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn unreadable_catalog_items_should_be_skipped_and_recorded() {
+        let storage = MemoryStorage::new();
+        storage
+            .save_project_platform(ProjectPlatform {
+                type_name: "arduino".to_string(),
+                name: "Arduino".to_string(),
+                description: String::new(),
+                capabilities: vec![],
+            })
+            .unwrap();
+        storage
+            .write()
+            .unwrap()
+            .project_platforms
+            .push(json!({ "type": "broken", "name": 42 }));
+
+        let platforms = storage.get_project_platforms(ReadMode::Lenient).unwrap();
+        assert_eq!(platforms.len(), 1);
+        assert_eq!(platforms[0].type_name, "arduino");
+
+        let read_failures = storage.get_read_failures().unwrap();
+        assert_eq!(read_failures.len(), 1);
+        assert_eq!(read_failures[0].collection, "project_platforms");
+        assert_eq!(read_failures[0].document_id, Some("broken".to_string()));
+
+        // Failure is recorded only once per document.
+        assert!(storage.get_project_platforms(ReadMode::Strict).is_err());
+        assert_eq!(storage.get_read_failures().unwrap().len(), 1);
+
+        storage.clear_read_failures().unwrap();
+        assert!(storage.get_read_failures().unwrap().is_empty());
+    }
+}
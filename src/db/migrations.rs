@@ -0,0 +1,203 @@
+use failure::{err_msg, Error};
+use serde_json::{Map, Value};
+
+/// Name of the document field that stores version of the document schema, documents without
+/// this field have version `0`.
+pub const SCHEMA_VERSION_FIELD: &str = "schemaVersion";
+
+/// Kind of the stored document that migrations are applied to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DocumentKind {
+    /// Project, either standalone or embedded into project revision or trashed project.
+    #[serde(rename(serialize = "project", deserialize = "project"))]
+    Project,
+    /// Component schema.
+    #[serde(rename(serialize = "componentSchema", deserialize = "componentSchema"))]
+    ComponentSchema,
+}
+
+/// Describes single migration that upgrades document of the specific kind from `version - 1` to
+/// `version`.
+pub struct Migration {
+    /// Kind of the documents this migration is applied to.
+    pub kind: DocumentKind,
+    /// Schema version of the document after migration.
+    pub version: i64,
+    /// Unique human-readable name of the migration.
+    pub name: &'static str,
+    /// Function that upgrades the document in place.
+    pub migrate: fn(&mut Map<String, Value>) -> Result<(), Error>,
+}
+
+/// Describes migration that has been applied to all stored documents.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AppliedMigration {
+    /// Kind of the documents migration has been applied to.
+    pub kind: DocumentKind,
+    /// Schema version of the documents after migration.
+    pub version: i64,
+    /// Name of the migration.
+    pub name: String,
+    /// Number of milliseconds elapsed since Unix epoch when migration was applied.
+    #[serde(rename(serialize = "appliedAt", deserialize = "appliedAt"))]
+    pub applied_at: i64,
+    /// Number of the stored documents that have been upgraded.
+    pub documents: i64,
+}
+
+/// All known migrations, versions of the migrations of the same kind should go in sequence
+/// starting from `1`. Migrations should never be changed or removed once released.
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        kind: DocumentKind::Project,
+        version: 1,
+        name: "add-missing-component-properties-and-triggers",
+        migrate: add_missing_component_properties_and_triggers,
+    },
+    Migration {
+        kind: DocumentKind::ComponentSchema,
+        version: 1,
+        name: "add-missing-schema-actions-and-triggers",
+        migrate: add_missing_schema_actions_and_triggers,
+    },
+];
+
+/// Returns schema version that all documents of the specified kind are upgraded to.
+pub fn current_version(kind: DocumentKind) -> i64 {
+    MIGRATIONS
+        .iter()
+        .filter(|migration| migration.kind == kind)
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Returns schema version of the document.
+pub fn document_version(document: &Map<String, Value>) -> i64 {
+    document
+        .get(SCHEMA_VERSION_FIELD)
+        .and_then(|version| version.as_i64())
+        .unwrap_or(0)
+}
+
+/// Applies all migrations of the specified kind that are newer than the document schema version
+/// and sets document schema version to the current one. Returns `false` if document is already
+/// up to date.
+///
+/// # Arguments
+///
+/// * `kind` - Kind of the document.
+/// * `document` - Document to upgrade, should be a JSON object.
+pub fn migrate_document(kind: DocumentKind, document: &mut Value) -> Result<bool, Error> {
+    let document = document
+        .as_object_mut()
+        .ok_or_else(|| err_msg("Only JSON objects can be migrated."))?;
+
+    let version = document_version(document);
+    if version >= current_version(kind) {
+        return Ok(false);
+    }
+
+    let mut migrations: Vec<&Migration> = MIGRATIONS
+        .iter()
+        .filter(|migration| migration.kind == kind && migration.version > version)
+        .collect();
+    migrations.sort_by_key(|migration| migration.version);
+
+    for migration in migrations {
+        (migration.migrate)(document)
+            .map_err(|err| err_msg(format!("Migration `{}` failed: {}", migration.name, err)))?;
+        document.insert(SCHEMA_VERSION_FIELD.to_string(), migration.version.into());
+    }
+
+    Ok(true)
+}
+
+/// Inserts empty object into the `field` unless it's already there.
+fn ensure_object_field(document: &mut Map<String, Value>, field: &str) {
+    document
+        .entry(field.to_string())
+        .or_insert_with(|| Value::Object(Map::new()));
+}
+
+/// Components of the projects saved before component properties and triggers were introduced
+/// don't have corresponding fields at all.
+fn add_missing_component_properties_and_triggers(
+    project: &mut Map<String, Value>,
+) -> Result<(), Error> {
+    // Components may be excluded from the document by the query projection.
+    if let Some(Value::Array(components)) = project.get_mut("components") {
+        for component in components {
+            let component = component
+                .as_object_mut()
+                .ok_or_else(|| err_msg("Project component should be a JSON object."))?;
+            ensure_object_field(component, "properties");
+            ensure_object_field(component, "triggers");
+        }
+    }
+
+    Ok(())
+}
+
+/// Component schemas saved before component actions and triggers were introduced don't have
+/// corresponding fields at all.
+fn add_missing_schema_actions_and_triggers(schema: &mut Map<String, Value>) -> Result<(), Error> {
+    ensure_object_field(schema, "actions");
+    ensure_object_field(schema, "triggers");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{current_version, migrate_document, DocumentKind, MIGRATIONS};
+    use projects::project::Project;
+    use serde_json;
+
+    #[test]
+    fn migrations_should_have_sequential_versions() {
+        for kind in &[DocumentKind::Project, DocumentKind::ComponentSchema] {
+            let mut versions: Vec<i64> = MIGRATIONS
+                .iter()
+                .filter(|migration| migration.kind == *kind)
+                .map(|migration| migration.version)
+                .collect();
+            versions.sort();
+
+            let expected_versions: Vec<i64> = (1..=versions.len() as i64).collect();
+            assert_eq!(versions, expected_versions);
+        }
+    }
+
+    #[test]
+    fn outdated_documents_should_be_migrated() {
+        let mut document = json!({
+            "id": "project-1",
+            "name": "project",
+            "description": "",
+            "capabilities": [],
+            "platform": "arduino",
+            "components": [{ "id": "led-1", "type": "led" }]
+        });
+
+        assert_eq!(
+            migrate_document(DocumentKind::Project, &mut document).unwrap(),
+            true
+        );
+        assert_eq!(
+            document["schemaVersion"],
+            json!(current_version(DocumentKind::Project))
+        );
+
+        let project: Project = serde_json::from_value(document.clone()).unwrap();
+        assert!(project.components[0].properties.is_empty());
+        assert!(project.components[0].triggers.is_empty());
+
+        // Up to date documents are left intact.
+        assert_eq!(
+            migrate_document(DocumentKind::Project, &mut document).unwrap(),
+            false
+        );
+        assert!(migrate_document(DocumentKind::Project, &mut json!([])).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod memory_storage;
+pub mod migrations;
+pub mod mongo_storage;
+pub mod seed;
+pub mod storage;
@@ -0,0 +1,1022 @@
+use bson;
+use failure::{err_msg, Error};
+use mongodb::coll::error::WriteException;
+use mongodb::coll::options::{FindOptions, IndexOptions, UpdateOptions};
+use mongodb::cursor::Cursor;
+use mongodb::db::ThreadedDatabase;
+use mongodb::error::Error as MongoDbError;
+use mongodb::{Client, ThreadedClient};
+use serde;
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::migrations::{self, AppliedMigration, DocumentKind, MIGRATIONS, SCHEMA_VERSION_FIELD};
+use super::storage::{read_documents, Page, ReadFailure, ReadMode, ReplaceResult, Storage};
+use components::component_group::ComponentGroup;
+use components::component_schema::ComponentSchema;
+use projects::project::Project;
+use projects::project_capability::ProjectCapability;
+use projects::project_capability_group::ProjectCapabilityGroup;
+use projects::project_platform::ProjectPlatform;
+use projects::project_query::{ProjectQuery, ProjectSortField};
+use projects::project_revision::{ProjectChange, ProjectRevision};
+use projects::project_search::query_terms;
+use projects::project_template::ProjectTemplate;
+use projects::trashed_project::TrashedProject;
+use utils::current_timestamp;
+
+/// Code of the write error MongoDB reports when unique index constraint is violated.
+const DUPLICATE_KEY_ERROR_CODE: i32 = 11000;
+
+/// Collections that contain versioned documents: collection name, kind of the documents and name
+/// of the field the document is embedded into, if any.
+const VERSIONED_COLLECTIONS: &[(&str, DocumentKind, Option<&str>)] = &[
+    ("projects", DocumentKind::Project, None),
+    ("project_revisions", DocumentKind::Project, Some("project")),
+    ("trashed_projects", DocumentKind::Project, Some("project")),
+    ("component_schemas", DocumentKind::ComponentSchema, None),
+    (
+        "component_schema_versions",
+        DocumentKind::ComponentSchema,
+        None,
+    ),
+];
+
+/// Returns versioned document of the specified kind that is either the `document` itself or
+/// embedded into it.
+fn versioned_document<'a>(
+    collection_name: &str,
+    document: &'a mut bson::Document,
+) -> Option<(DocumentKind, &'a mut bson::Document)> {
+    let (_, kind, field) = VERSIONED_COLLECTIONS
+        .iter()
+        .find(|(name, _, _)| *name == collection_name)?;
+
+    match field {
+        Some(field) => match document.get_mut(field) {
+            Some(bson::Bson::Document(embedded_document)) => Some((*kind, embedded_document)),
+            _ => None,
+        },
+        None => Some((*kind, document)),
+    }
+}
+
+/// Serializes item into the document, documents of the versioned collections are marked with the
+/// current schema version.
+fn to_document<T>(collection_name: &str, item: &T) -> Result<bson::Document, Error>
+where
+    T: serde::Serialize,
+{
+    let mut document = match bson::to_bson(item)? {
+        bson::Bson::Document(document) => document,
+        _ => return Err(err_msg("Item should be serialized into a document.")),
+    };
+
+    if let Some((kind, versioned_document)) = versioned_document(collection_name, &mut document) {
+        versioned_document.insert(SCHEMA_VERSION_FIELD, migrations::current_version(kind));
+    }
+
+    Ok(document)
+}
+
+/// Upgrades outdated document of the versioned collection to the current schema version, returns
+/// `false` if document is already up to date.
+fn upgrade_document(collection_name: &str, document: &mut bson::Document) -> Result<bool, Error> {
+    let (kind, versioned) = match versioned_document(collection_name, document) {
+        Some(versioned) => versioned,
+        None => return Ok(false),
+    };
+
+    let version = match versioned.get(SCHEMA_VERSION_FIELD) {
+        Some(&bson::Bson::I32(version)) => i64::from(version),
+        Some(&bson::Bson::I64(version)) => version,
+        _ => 0,
+    };
+    if version >= migrations::current_version(kind) {
+        return Ok(false);
+    }
+
+    // Object identifier doesn't have JSON representation and migrations don't need it anyway.
+    let object_id = versioned.remove("_id");
+
+    let mut value: Value = bson::from_bson(bson::Bson::Document(versioned.clone()))?;
+    migrations::migrate_document(kind, &mut value)?;
+    *versioned = match json_to_bson(value) {
+        bson::Bson::Document(upgraded_document) => upgraded_document,
+        _ => return Err(err_msg("Migrated document should be a JSON object.")),
+    };
+
+    if let Some(object_id) = object_id {
+        versioned.insert("_id", object_id);
+    }
+
+    Ok(true)
+}
+
+/// Upgrades the document if needed and deserializes it.
+fn read_document<T>(collection_name: &str, mut document: bson::Document) -> Result<T, Error>
+where
+    T: serde::de::DeserializeOwned,
+{
+    upgrade_document(collection_name, &mut document)?;
+    Ok(bson::from_bson(bson::Bson::Document(document))?)
+}
+
+/// Returns human-readable identifier of the document: project or template identifier, catalog
+/// item type or MongoDB object identifier, whatever is available.
+fn document_id(document: &bson::Document) -> Option<String> {
+    let embedded_project_id = match document.get("project") {
+        Some(bson::Bson::Document(project)) => project.get("id"),
+        _ => None,
+    };
+
+    document
+        .get("id")
+        .or_else(|| document.get("type"))
+        .or(embedded_project_id)
+        .or_else(|| document.get("_id"))
+        .map(|id| match id {
+            bson::Bson::String(id) => id.clone(),
+            bson::Bson::ObjectId(id) => id.to_hex(),
+            id => id.to_string(),
+        })
+}
+
+/// Checks whether write has been rejected by one of the unique indexes.
+fn is_duplicate_key(write_exception: &WriteException) -> bool {
+    write_exception
+        .write_error
+        .as_ref()
+        .map_or(false, |write_error| {
+            write_error.code == DUPLICATE_KEY_ERROR_CODE
+        })
+}
+
+/// Converts JSON value into BSON, integer numbers are always converted to 64-bit integers.
+fn json_to_bson(value: Value) -> bson::Bson {
+    match value {
+        Value::Null => bson::Bson::Null,
+        Value::Bool(value) => bson::Bson::Boolean(value),
+        Value::Number(number) => match number.as_i64() {
+            Some(number) => bson::Bson::I64(number),
+            None => bson::Bson::FloatingPoint(number.as_f64().unwrap_or_default()),
+        },
+        Value::String(value) => bson::Bson::String(value),
+        Value::Array(values) => bson::Bson::Array(values.into_iter().map(json_to_bson).collect()),
+        Value::Object(values) => bson::Bson::Document(
+            values
+                .into_iter()
+                .map(|(key, value)| (key, json_to_bson(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Storage backed by the MongoDB database.
+#[derive(Clone)]
+pub struct MongoStorage {
+    name: String,
+    client: Option<Client>,
+}
+
+impl MongoStorage {
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        MongoStorage {
+            name: name.into(),
+            client: None,
+        }
+    }
+
+    pub fn connect(&mut self, host: &str, port: u16) -> Result<(), Error> {
+        self.client = Some(Client::connect(host, port)?);
+        Ok(())
+    }
+
+    /// Creates unique indexes the storage relies on to reject duplicates (e.g. concurrent inserts
+    /// of the projects with the same identifier), creating index that already exists is a no-op.
+    pub fn create_indexes(&self) -> Result<(), Error> {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+
+        let unique_indexes = vec![
+            ("projects", doc! { "id" => 1 }),
+            (
+                "project_revisions",
+                doc! { "projectId" => 1, "revision" => -1 },
+            ),
+            ("trashed_projects", doc! { "project.id" => 1 }),
+            ("project_templates", doc! { "id" => 1 }),
+            ("component_groups", doc! { "type" => 1 }),
+            ("component_schemas", doc! { "type" => 1 }),
+            (
+                "component_schema_versions",
+                doc! { "type" => 1, "version" => -1 },
+            ),
+            ("project_capability_groups", doc! { "type" => 1 }),
+            ("project_capabilities", doc! { "type" => 1 }),
+            ("project_platforms", doc! { "type" => 1 }),
+            ("migrations", doc! { "name" => 1 }),
+        ];
+
+        for (collection_name, keys) in unique_indexes {
+            let mut options = IndexOptions::new();
+            options.unique = Some(true);
+
+            db.collection(collection_name)
+                .create_index(keys, Some(options))?;
+        }
+
+        Ok(())
+    }
+
+    fn get_collection<T>(&self, collection_name: &str, read_mode: ReadMode) -> Result<Vec<T>, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.find(collection_name, None, None, read_mode)
+    }
+
+    /// Queries single document, document that can't be read is always treated as an error.
+    fn find_one<T>(&self, collection_name: &str, filter: bson::Document) -> Result<Option<T>, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+
+        let document = db
+            .collection(collection_name)
+            .find_one(Some(filter), None)?
+            .map(|document| {
+                (
+                    document_id(&document),
+                    read_document(collection_name, document),
+                )
+            });
+
+        let mut items = read_documents(collection_name, document, ReadMode::Strict, |failure| {
+            self.record_read_failure(&failure)
+        })?;
+
+        Ok(items.pop())
+    }
+
+    fn find<T>(
+        &self,
+        collection_name: &str,
+        filter: Option<bson::Document>,
+        options: Option<FindOptions>,
+        read_mode: ReadMode,
+    ) -> Result<Vec<T>, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+
+        let cursor = db.collection(collection_name).find(filter, options)?;
+        self.read_cursor(collection_name, cursor, read_mode)
+    }
+
+    fn read_cursor<T>(
+        &self,
+        collection_name: &str,
+        cursor: Cursor,
+        read_mode: ReadMode,
+    ) -> Result<Vec<T>, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let documents = cursor.map(|cursor_item| {
+            info!("Iterating through database records {:?}", cursor_item);
+            match cursor_item {
+                Ok(document) => (
+                    document_id(&document),
+                    read_document(collection_name, document),
+                ),
+                Err(err) => (None, Err(err.into())),
+            }
+        });
+
+        read_documents(collection_name, documents, read_mode, |failure| {
+            self.record_read_failure(&failure)
+        })
+    }
+
+    /// Records document that can't be read, so that it can be inspected later. Failure to record
+    /// is only logged since the document itself has already been handled.
+    fn record_read_failure(&self, failure: &ReadFailure) {
+        let failure_filter = doc! {
+            "collection" => failure.collection.clone(),
+            "documentId" => failure
+                .document_id
+                .clone()
+                .map_or(bson::Bson::Null, bson::Bson::String)
+        };
+        if let Err(record_err) = self.upsert("read_failures", failure_filter, failure) {
+            error!("Failed to record read failure: {}", record_err);
+        }
+    }
+
+    /// Replaces document that matches the filter with the `item` or inserts it if there is no
+    /// such document.
+    fn upsert<T>(
+        &self,
+        collection_name: &str,
+        filter: bson::Document,
+        item: &T,
+    ) -> Result<(), Error>
+    where
+        T: serde::Serialize,
+    {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+
+        let mut options = UpdateOptions::new();
+        options.upsert = Some(true);
+
+        let collection = db.collection(collection_name);
+        let result =
+            collection.replace_one(filter, to_document(collection_name, item)?, Some(options))?;
+        if let Some(write_exception) = result.write_exception {
+            return Err(MongoDbError::WriteError(write_exception).into());
+        }
+
+        Ok(())
+    }
+
+    /// Deletes single document that matches the filter, returns `false` if nothing was deleted.
+    fn delete_one(&self, collection_name: &str, filter: bson::Document) -> Result<bool, Error> {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+
+        let result = db.collection(collection_name).delete_one(filter, None)?;
+        if let Some(write_exception) = result.write_exception {
+            return Err(MongoDbError::WriteError(write_exception).into());
+        }
+
+        Ok(result.deleted_count > 0)
+    }
+
+    /// Upgrades all outdated documents of the versioned collection and saves them back, returns
+    /// number of the upgraded documents.
+    fn upgrade_collection(&self, collection_name: &str) -> Result<i64, Error> {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+        let collection = db.collection(collection_name);
+
+        let mut upgraded_documents = 0;
+        for document in collection.find(None, None)? {
+            let mut document = document?;
+            if !upgrade_document(collection_name, &mut document)? {
+                continue;
+            }
+
+            if let Some(object_id) = document.get("_id").cloned() {
+                let result = collection.replace_one(doc! { "_id" => object_id }, document, None)?;
+                if let Some(write_exception) = result.write_exception {
+                    return Err(MongoDbError::WriteError(write_exception).into());
+                }
+
+                upgraded_documents += 1;
+            }
+        }
+
+        Ok(upgraded_documents)
+    }
+
+    fn insert_project_revision(&self, revision: &ProjectRevision) -> Result<(), Error> {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+
+        db.collection("project_revisions")
+            .insert_one(to_document("project_revisions", revision)?, None)?;
+
+        Ok(())
+    }
+}
+
+impl Storage for MongoStorage {
+    fn get_project(&self, project_id: &str) -> Result<Option<Project>, Error> {
+        self.find_one("projects", doc! { "id" => project_id })
+    }
+
+    fn trash_project(&self, project_id: &str, change: ProjectChange) -> Result<bool, Error> {
+        let project = match self.get_project(project_id)? {
+            Some(project) => project,
+            None => return Ok(false),
+        };
+
+        let db = self.client.as_ref().unwrap().db(&self.name);
+
+        // Project is copied to the trash first so that it's never lost, if project has been
+        // deleted concurrently the copy is removed.
+        let trashed_project = TrashedProject {
+            project,
+            deleted_at: current_timestamp(),
+            deleted_by: change.author,
+        };
+        db.collection("trashed_projects")
+            .insert_one(to_document("trashed_projects", &trashed_project)?, None)?;
+
+        if self.delete_one("projects", doc! { "id" => project_id })? {
+            return Ok(true);
+        }
+
+        self.delete_one(
+            "trashed_projects",
+            doc! { "project.id" => project_id, "deletedAt" => trashed_project.deleted_at },
+        )?;
+
+        Ok(false)
+    }
+
+    fn get_trashed_projects(&self, read_mode: ReadMode) -> Result<Vec<TrashedProject>, Error> {
+        let mut options = FindOptions::new();
+        options.sort = Some(doc! { "deletedAt" => -1 });
+
+        self.find("trashed_projects", None, Some(options), read_mode)
+    }
+
+    fn restore_trashed_project(&self, project_id: &str) -> Result<Option<Project>, Error> {
+        let trashed_project: TrashedProject =
+            match self.find_one("trashed_projects", doc! { "project.id" => project_id })? {
+                Some(trashed_project) => trashed_project,
+                None => return Ok(None),
+            };
+
+        let db = self.client.as_ref().unwrap().db(&self.name);
+        db.collection("projects")
+            .insert_one(to_document("projects", &trashed_project.project)?, None)?;
+
+        self.delete_one("trashed_projects", doc! { "project.id" => project_id })?;
+
+        Ok(Some(trashed_project.project))
+    }
+
+    fn purge_trashed_project(&self, project_id: &str) -> Result<bool, Error> {
+        if !self.delete_one("trashed_projects", doc! { "project.id" => project_id })? {
+            return Ok(false);
+        }
+
+        let db = self.client.as_ref().unwrap().db(&self.name);
+        db.collection("project_revisions")
+            .delete_many(doc! { "projectId" => project_id }, None)?;
+
+        Ok(true)
+    }
+
+    fn purge_trashed_projects(&self, deleted_before: i64) -> Result<Vec<String>, Error> {
+        let expired_projects: Vec<TrashedProject> = self.find(
+            "trashed_projects",
+            Some(doc! { "deletedAt" => doc! { "$lt" => deleted_before } }),
+            None,
+            // Projects that can't be read are kept until they are fixed or purged explicitly.
+            ReadMode::Lenient,
+        )?;
+
+        let mut purged_project_ids = vec![];
+        for expired_project in expired_projects {
+            if self.purge_trashed_project(&expired_project.project.id)? {
+                purged_project_ids.push(expired_project.project.id);
+            }
+        }
+
+        Ok(purged_project_ids)
+    }
+
+    fn get_projects(&self, read_mode: ReadMode) -> Result<Vec<Project>, Error> {
+        self.get_collection("projects", read_mode)
+    }
+
+    fn search_projects(&self, query: &str, read_mode: ReadMode) -> Result<Vec<Project>, Error> {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+
+        let terms = query_terms(query);
+        if terms.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // Collects name, description, component types and component property values into the
+        // single array, so that every term can be matched against any of them.
+        let property_values = doc! {
+            "$map" => doc! {
+                "input" => doc! {
+                    "$objectToArray" => doc! {
+                        "$ifNull" => vec![
+                            bson::Bson::String("$$this.properties".to_string()),
+                            bson::Bson::Document(doc! {}),
+                        ]
+                    }
+                },
+                "as" => "property",
+                "in" => "$$property.v"
+            }
+        };
+        let search_values = doc! {
+            "$concatArrays" => vec![
+                bson::Bson::Array(vec![
+                    bson::Bson::String("$name".to_string()),
+                    bson::Bson::String("$description".to_string()),
+                ]),
+                bson::Bson::String("$components.type".to_string()),
+                bson::Bson::Document(doc! {
+                    "$reduce" => doc! {
+                        "input" => "$components",
+                        "initialValue" => bson::Bson::Array(vec![]),
+                        "in" => doc! {
+                            "$concatArrays" => vec![
+                                bson::Bson::String("$$value".to_string()),
+                                bson::Bson::Document(property_values),
+                            ]
+                        }
+                    }
+                }),
+            ]
+        };
+
+        // Terms consist of alphanumeric characters only, so they don't need to be escaped.
+        let term_filters = terms
+            .into_iter()
+            .map(|term| {
+                bson::Bson::Document(doc! {
+                    "searchValues" => doc! { "$regex" => term, "$options" => "i" }
+                })
+            }).collect::<Vec<_>>();
+
+        let pipeline = vec![
+            doc! { "$addFields" => doc! { "searchValues" => search_values } },
+            doc! { "$match" => doc! { "$and" => term_filters } },
+            doc! { "$project" => doc! { "searchValues" => 0 } },
+        ];
+
+        let cursor = db.collection("projects").aggregate(pipeline, None)?;
+        self.read_cursor("projects", cursor, read_mode)
+    }
+
+    fn find_projects(
+        &self,
+        query: &ProjectQuery,
+        read_mode: ReadMode,
+    ) -> Result<Page<Project>, Error> {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+
+        let mut filter = doc! {};
+        if let Some(ref platform) = query.platform {
+            filter.insert("platform", platform.clone());
+        }
+
+        if !query.capabilities.is_empty() {
+            let capabilities = query
+                .capabilities
+                .iter()
+                .cloned()
+                .map(bson::Bson::String)
+                .collect::<Vec<_>>();
+            filter.insert("capabilities", doc! { "$all" => capabilities });
+        }
+
+        let sort_direction = if query.sort_descending { -1 } else { 1 };
+        let sort_field = match query.sort_field {
+            ProjectSortField::Name => "name",
+            ProjectSortField::CreatedAt => "createdAt",
+            ProjectSortField::UpdatedAt => "updatedAt",
+        };
+
+        let mut options = FindOptions::new();
+        // Sort by identifier as well to have stable order for the projects with equal field values.
+        options.sort = Some(doc! { sort_field => sort_direction, "id" => 1 });
+        options.skip = Some(query.offset as i64);
+        options.limit = Some(query.limit as i64);
+        if !query.include_components {
+            options.projection = Some(doc! { "components" => 0 });
+        }
+
+        let total = db
+            .collection("projects")
+            .count(Some(filter.clone()), None)?;
+
+        Ok(Page {
+            items: self.find("projects", Some(filter), Some(options), read_mode)?,
+            total: total as u64,
+        })
+    }
+
+    fn insert_project(
+        &self,
+        mut project: Project,
+        change: ProjectChange,
+    ) -> Result<Option<Project>, Error> {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+
+        if project.id.is_empty() {
+            project.id = Uuid::new_v4().to_string();
+        }
+        project.revision = 1;
+        project.track_creation(change.author, current_timestamp());
+
+        if self.get_project(&project.id)?.is_some() {
+            return Ok(None);
+        }
+
+        // Unique index on the project identifier rejects concurrent inserts of the same project.
+        let result = db
+            .collection("projects")
+            .insert_one(to_document("projects", &project)?, None)?;
+        if let Some(write_exception) = result.write_exception {
+            if is_duplicate_key(&write_exception) {
+                return Ok(None);
+            }
+
+            return Err(MongoDbError::WriteError(write_exception).into());
+        }
+
+        self.insert_project_revision(&ProjectRevision::new(&project, change.message))?;
+
+        Ok(Some(project))
+    }
+
+    fn replace_project(
+        &self,
+        mut project: Project,
+        expected_revision: i64,
+        change: ProjectChange,
+    ) -> Result<ReplaceResult<Project>, Error> {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+        let collection = db.collection("projects");
+
+        // Current project is needed to preserve its creation metadata, revision filter below still
+        // guards against the concurrent modifications.
+        let current_project = match self.get_project(&project.id)? {
+            Some(ref current_project) if current_project.revision != expected_revision => {
+                return Ok(ReplaceResult::Conflict(current_project.revision))
+            }
+            Some(current_project) => current_project,
+            None => return Ok(ReplaceResult::NotFound),
+        };
+
+        project.revision = expected_revision + 1;
+        project.track_update(&current_project, change.author, current_timestamp());
+
+        // Projects saved before revisions were introduced don't have `revision` field at all.
+        let mut filter = doc! { "id" => &project.id };
+        if expected_revision == 0 {
+            filter.insert(
+                "revision",
+                doc! { "$in" => vec![bson::Bson::I64(0), bson::Bson::Null] },
+            );
+        } else {
+            filter.insert("revision", expected_revision);
+        }
+
+        let result = collection.replace_one(filter, to_document("projects", &project)?, None)?;
+
+        if let Some(write_exception) = result.write_exception {
+            return Err(MongoDbError::WriteError(write_exception).into());
+        }
+
+        if result.matched_count == 0 {
+            return Ok(match self.get_project(&project.id)? {
+                Some(current_project) => ReplaceResult::Conflict(current_project.revision),
+                None => ReplaceResult::NotFound,
+            });
+        }
+
+        self.insert_project_revision(&ProjectRevision::new(&project, change.message))?;
+
+        Ok(ReplaceResult::Replaced(project))
+    }
+
+    fn get_project_revisions(
+        &self,
+        project_id: &str,
+        read_mode: ReadMode,
+    ) -> Result<Vec<ProjectRevision>, Error> {
+        let mut options = FindOptions::new();
+        options.sort = Some(doc! { "revision" => -1 });
+
+        self.find(
+            "project_revisions",
+            Some(doc! { "projectId" => project_id }),
+            Some(options),
+            read_mode,
+        )
+    }
+
+    fn get_project_revision(
+        &self,
+        project_id: &str,
+        revision: i64,
+    ) -> Result<Option<ProjectRevision>, Error> {
+        self.find_one(
+            "project_revisions",
+            doc! { "projectId" => project_id, "revision" => revision },
+        )
+    }
+
+    fn get_component_groups(&self, read_mode: ReadMode) -> Result<Vec<ComponentGroup>, Error> {
+        self.get_collection("component_groups", read_mode)
+    }
+
+    fn get_component_schemas(&self, read_mode: ReadMode) -> Result<Vec<ComponentSchema>, Error> {
+        self.get_collection("component_schemas", read_mode)
+    }
+
+    fn save_component_group(&self, group: ComponentGroup) -> Result<(), Error> {
+        self.upsert(
+            "component_groups",
+            doc! { "type" => group.type_name.clone() },
+            &group,
+        )
+    }
+
+    fn get_component_schema_versions(
+        &self,
+        type_name: &str,
+        read_mode: ReadMode,
+    ) -> Result<Vec<ComponentSchema>, Error> {
+        let mut options = FindOptions::new();
+        options.sort = Some(doc! { "version" => -1 });
+
+        let mut schemas: Vec<ComponentSchema> = self.find(
+            "component_schemas",
+            Some(doc! { "type" => type_name }),
+            None,
+            read_mode,
+        )?;
+        schemas.extend(self.find(
+            "component_schema_versions",
+            Some(doc! { "type" => type_name }),
+            Some(options),
+            read_mode,
+        )?);
+
+        Ok(schemas)
+    }
+
+    fn get_component_schema_version(
+        &self,
+        type_name: &str,
+        version: i64,
+    ) -> Result<Option<ComponentSchema>, Error> {
+        let filter = doc! { "type" => type_name, "version" => version };
+        match self.find_one("component_schemas", filter.clone())? {
+            Some(schema) => Ok(Some(schema)),
+            None => self.find_one("component_schema_versions", filter),
+        }
+    }
+
+    fn save_component_schema(
+        &self,
+        schema: ComponentSchema,
+        expected_version: Option<i64>,
+    ) -> Result<ReplaceResult<ComponentSchema>, Error> {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+        let collection = db.collection("component_schemas");
+        let type_filter = doc! { "type" => schema.type_name.clone() };
+
+        let current_schema: Option<ComponentSchema> =
+            self.find_one("component_schemas", type_filter.clone())?;
+        let expected_version = match (current_schema, expected_version) {
+            (Some(ref current_schema), Some(expected_version))
+                if current_schema.version == expected_version =>
+            {
+                if current_schema.version != schema.version {
+                    self.upsert(
+                        "component_schema_versions",
+                        doc! {
+                            "type" => current_schema.type_name.clone(),
+                            "version" => current_schema.version
+                        },
+                        current_schema,
+                    )?;
+                }
+                expected_version
+            }
+            (Some(current_schema), _) => {
+                return Ok(ReplaceResult::Conflict(current_schema.version))
+            }
+            (None, Some(_)) => return Ok(ReplaceResult::NotFound),
+            (None, None) => {
+                // Unique index on the schema type rejects concurrent inserts of the same schema.
+                let result =
+                    collection.insert_one(to_document("component_schemas", &schema)?, None)?;
+                if let Some(write_exception) = result.write_exception {
+                    if !is_duplicate_key(&write_exception) {
+                        return Err(MongoDbError::WriteError(write_exception).into());
+                    }
+
+                    return Ok(match self.find_one("component_schemas", type_filter)? {
+                        Some(ComponentSchema { version, .. }) => ReplaceResult::Conflict(version),
+                        None => ReplaceResult::NotFound,
+                    });
+                }
+
+                return Ok(ReplaceResult::Replaced(schema));
+            }
+        };
+
+        let mut filter = type_filter.clone();
+        filter.insert("version", expected_version);
+
+        let result =
+            collection.replace_one(filter, to_document("component_schemas", &schema)?, None)?;
+        if let Some(write_exception) = result.write_exception {
+            return Err(MongoDbError::WriteError(write_exception).into());
+        }
+
+        if result.matched_count == 0 {
+            return Ok(match self.find_one("component_schemas", type_filter)? {
+                Some(ComponentSchema { version, .. }) => ReplaceResult::Conflict(version),
+                None => ReplaceResult::NotFound,
+            });
+        }
+
+        Ok(ReplaceResult::Replaced(schema))
+    }
+
+    fn save_component_schema_version(&self, schema: ComponentSchema) -> Result<(), Error> {
+        self.upsert(
+            "component_schema_versions",
+            doc! {
+                "type" => schema.type_name.clone(),
+                "version" => schema.version
+            },
+            &schema,
+        )
+    }
+
+    fn delete_component_group(&self, type_name: &str) -> Result<bool, Error> {
+        self.delete_one("component_groups", doc! { "type" => type_name })
+    }
+
+    fn delete_component_schema(&self, type_name: &str) -> Result<bool, Error> {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+
+        let result = db
+            .collection("component_schema_versions")
+            .delete_many(doc! { "type" => type_name }, None)?;
+        if let Some(write_exception) = result.write_exception {
+            return Err(MongoDbError::WriteError(write_exception).into());
+        }
+
+        self.delete_one("component_schemas", doc! { "type" => type_name })
+    }
+
+    fn get_component_type_usages(&self, type_name: &str) -> Result<Vec<String>, Error> {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+
+        // Only identifiers are needed, so documents aren't deserialized into projects at all.
+        let mut project_ids = vec![];
+        for (collection_name, prefix) in &[("projects", ""), ("trashed_projects", "project.")] {
+            let id_field = format!("{}id", prefix);
+            let type_field = format!("{}components.type", prefix);
+
+            let mut options = FindOptions::new();
+            options.projection = Some(doc! { id_field => 1 });
+
+            let cursor = db
+                .collection(collection_name)
+                .find(Some(doc! { type_field => type_name }), Some(options))?;
+            for document in cursor {
+                let document = document?;
+                let project_id = if prefix.is_empty() {
+                    document.get_str("id")
+                } else {
+                    document
+                        .get_document("project")
+                        .and_then(|project| project.get_str("id"))
+                };
+
+                if let Ok(project_id) = project_id {
+                    project_ids.push(project_id.to_string());
+                }
+            }
+        }
+        project_ids.sort();
+
+        Ok(project_ids)
+    }
+
+    fn get_project_capability_groups(
+        &self,
+        read_mode: ReadMode,
+    ) -> Result<Vec<ProjectCapabilityGroup>, Error> {
+        self.get_collection("project_capability_groups", read_mode)
+    }
+
+    fn get_project_capabilities(
+        &self,
+        read_mode: ReadMode,
+    ) -> Result<Vec<ProjectCapability>, Error> {
+        self.get_collection("project_capabilities", read_mode)
+    }
+
+    fn get_project_platforms(&self, read_mode: ReadMode) -> Result<Vec<ProjectPlatform>, Error> {
+        self.get_collection("project_platforms", read_mode)
+    }
+
+    fn save_project_capability_group(&self, group: ProjectCapabilityGroup) -> Result<(), Error> {
+        self.upsert(
+            "project_capability_groups",
+            doc! { "type" => group.type_name.clone() },
+            &group,
+        )
+    }
+
+    fn save_project_capability(&self, capability: ProjectCapability) -> Result<(), Error> {
+        self.upsert(
+            "project_capabilities",
+            doc! { "type" => capability.type_name.clone() },
+            &capability,
+        )
+    }
+
+    fn save_project_platform(&self, platform: ProjectPlatform) -> Result<(), Error> {
+        self.upsert(
+            "project_platforms",
+            doc! { "type" => platform.type_name.clone() },
+            &platform,
+        )
+    }
+
+    fn get_project_templates(&self, read_mode: ReadMode) -> Result<Vec<ProjectTemplate>, Error> {
+        self.get_collection("project_templates", read_mode)
+    }
+
+    fn get_project_template(&self, template_id: &str) -> Result<Option<ProjectTemplate>, Error> {
+        self.find_one("project_templates", doc! { "id" => template_id })
+    }
+
+    fn save_project_template(&self, template: ProjectTemplate) -> Result<(), Error> {
+        self.upsert(
+            "project_templates",
+            doc! { "id" => &template.id },
+            &template,
+        )
+    }
+
+    fn migrate(&self) -> Result<Vec<AppliedMigration>, Error> {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+
+        self.create_indexes()?;
+
+        let applied_migrations: Vec<AppliedMigration> =
+            self.find("migrations", None, None, ReadMode::Strict)?;
+        let pending_migrations: Vec<_> = MIGRATIONS
+            .iter()
+            .filter(|migration| {
+                !applied_migrations
+                    .iter()
+                    .any(|applied_migration| applied_migration.name == migration.name)
+            }).collect();
+
+        // Every document is upgraded to the current version at once, so all pending migrations
+        // of the same kind share the number of upgraded documents.
+        let mut new_migrations = vec![];
+        for kind in &[DocumentKind::Project, DocumentKind::ComponentSchema] {
+            if !pending_migrations
+                .iter()
+                .any(|migration| migration.kind == *kind)
+            {
+                continue;
+            }
+
+            let mut upgraded_documents = 0;
+            for (collection_name, _, _) in VERSIONED_COLLECTIONS
+                .iter()
+                .filter(|(_, collection_kind, _)| collection_kind == kind)
+            {
+                upgraded_documents += self.upgrade_collection(collection_name)?;
+            }
+
+            for migration in pending_migrations
+                .iter()
+                .filter(|migration| migration.kind == *kind)
+            {
+                let applied_migration = AppliedMigration {
+                    kind: migration.kind,
+                    version: migration.version,
+                    name: migration.name.to_string(),
+                    applied_at: current_timestamp(),
+                    documents: upgraded_documents,
+                };
+
+                db.collection("migrations")
+                    .insert_one(to_document("migrations", &applied_migration)?, None)?;
+                info!("Migration `{}` has been applied.", applied_migration.name);
+
+                new_migrations.push(applied_migration);
+            }
+        }
+
+        Ok(new_migrations)
+    }
+
+    fn get_read_failures(&self) -> Result<Vec<ReadFailure>, Error> {
+        let mut options = FindOptions::new();
+        options.sort = Some(doc! { "occurredAt" => -1 });
+
+        self.find("read_failures", None, Some(options), ReadMode::Strict)
+    }
+
+    fn clear_read_failures(&self) -> Result<(), Error> {
+        let db = self.client.as_ref().unwrap().db(&self.name);
+        db.collection("read_failures").delete_many(doc! {}, None)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,684 @@
+use std::cmp;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::Path;
+
+use failure::{err_msg, Error};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{self, Value};
+
+use super::storage::{ReadMode, ReplaceResult, Storage};
+use components::component_group::ComponentGroup;
+use components::component_schema::ComponentSchema;
+use components::component_schema_inheritance::resolve_schemas;
+use projects::project_capability::ProjectCapability;
+use projects::project_capability_group::ProjectCapabilityGroup;
+use projects::project_platform::ProjectPlatform;
+use projects::project_template::ProjectTemplate;
+use validation::catalog_validator::{validate_component_group, validate_component_schema};
+use validation::validation_error::ValidationErrors;
+
+const COMPONENT_GROUPS_FILE: &str = "component_groups.json";
+const COMPONENT_SCHEMAS_FILE: &str = "component_schemas.json";
+const COMPONENT_SCHEMA_VERSIONS_FILE: &str = "component_schema_versions.json";
+const PROJECT_CAPABILITY_GROUPS_FILE: &str = "project_capability_groups.json";
+const PROJECT_CAPABILITIES_FILE: &str = "project_capabilities.json";
+const PROJECT_PLATFORMS_FILE: &str = "project_platforms.json";
+const PROJECT_TEMPLATES_FILE: &str = "project_templates.json";
+
+/// Reads items from all `*.json` files in the directory, every file contains either single item
+/// or an array of items. Files are read in alphabetical order.
+///
+/// # Arguments
+///
+/// * `directory` - Directory to read JSON files from.
+pub fn read_json_items<T>(directory: &Path) -> Result<Vec<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    let mut paths = vec![];
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let is_json = path
+            .extension()
+            .map_or(false, |extension| extension == "json");
+        if path.is_file() && is_json {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut items = vec![];
+    for path in paths {
+        items.extend(read_json_file(&path)?);
+    }
+
+    Ok(items)
+}
+
+/// Reads items from the JSON file that contains either single item or an array of items.
+///
+/// # Arguments
+///
+/// * `path` - Path to the JSON file.
+pub fn read_json_file<T>(path: &Path) -> Result<Vec<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    let value: Value = serde_json::from_reader(File::open(path)?)
+        .map_err(|err| err_msg(format!("File {:?} is not valid JSON: {}", path, err)))?;
+    let values = match value {
+        Value::Array(values) => values,
+        value => vec![value],
+    };
+
+    values
+        .into_iter()
+        .map(|value| {
+            serde_json::from_value(value)
+                .map_err(|err| err_msg(format!("File {:?} contains invalid item: {}", path, err)))
+        }).collect()
+}
+
+/// Saves all project templates from JSON files in the directory to the storage, templates with
+/// the same identifiers are replaced. Returns number of the saved templates.
+///
+/// # Arguments
+///
+/// * `storage` - Storage to save templates to.
+/// * `directory` - Directory to read template JSON files from.
+pub fn seed_project_templates(storage: &dyn Storage, directory: &Path) -> Result<usize, Error> {
+    let templates: Vec<ProjectTemplate> = read_json_items(directory)?;
+    let templates_count = templates.len();
+    for template in templates {
+        storage.save_project_template(template)?;
+    }
+
+    Ok(templates_count)
+}
+
+/// Saves all catalogs from JSON files in the directory to the storage, catalog items with the
+/// same types (or identifiers for the project templates) are replaced, so seeding is idempotent.
+/// Every catalog is stored in its own file that contains an array of the catalog items, catalogs
+/// without a file are skipped. All catalogs are read and validated before anything is saved, so
+/// that invalid catalog doesn't leave the storage half-seeded, the first invalid item fails the
+/// whole seeding. Changed component schemas get a new version instead of the one from the file
+/// and replaced ones are kept as previous versions, previous versions from the file are only
+/// added. Returns names of the seeded catalog files with the number of saved items.
+///
+/// # Arguments
+///
+/// * `storage` - Storage to save catalogs to.
+/// * `directory` - Directory to read catalog JSON files from.
+pub fn seed_catalogs(
+    storage: &dyn Storage,
+    directory: &Path,
+) -> Result<Vec<(&'static str, usize)>, Error> {
+    let groups: Option<Vec<ComponentGroup>> = read_catalog(directory, COMPONENT_GROUPS_FILE)?;
+    let mut schemas: Option<Vec<ComponentSchema>> =
+        read_catalog(directory, COMPONENT_SCHEMAS_FILE)?;
+    let schema_versions: Option<Vec<ComponentSchema>> =
+        read_catalog(directory, COMPONENT_SCHEMA_VERSIONS_FILE)?;
+    let capability_groups: Option<Vec<ProjectCapabilityGroup>> =
+        read_catalog(directory, PROJECT_CAPABILITY_GROUPS_FILE)?;
+    let capabilities: Option<Vec<ProjectCapability>> =
+        read_catalog(directory, PROJECT_CAPABILITIES_FILE)?;
+    let platforms: Option<Vec<ProjectPlatform>> = read_catalog(directory, PROJECT_PLATFORMS_FILE)?;
+    let templates: Option<Vec<ProjectTemplate>> = read_catalog(directory, PROJECT_TEMPLATES_FILE)?;
+
+    let stored_schemas = storage.get_component_schemas(ReadMode::Strict)?;
+    let stored_versions: HashMap<String, i64> = stored_schemas
+        .iter()
+        .map(|schema| (schema.type_name.clone(), schema.version))
+        .collect();
+
+    validate_catalog(
+        directory,
+        COMPONENT_SCHEMA_VERSIONS_FILE,
+        &schema_versions,
+        validate_component_schema,
+    )?;
+    let schema_versions = match schema_versions {
+        Some(schema_versions) => Some(new_schema_versions(
+            storage,
+            directory,
+            schema_versions,
+            &stored_schemas,
+            schemas.as_ref().map(Vec::as_slice).unwrap_or(&[]),
+        )?),
+        None => None,
+    };
+    if let Some(ref mut schemas) = schemas {
+        assign_schema_versions(
+            storage,
+            schemas,
+            &stored_schemas,
+            schema_versions.as_ref().map(Vec::as_slice).unwrap_or(&[]),
+        )?;
+    }
+
+    // Seeded schemas and groups are checked together with the schemas that are already stored.
+    let all_schemas = merge_schemas(
+        stored_schemas,
+        schemas.as_ref().map(Vec::as_slice).unwrap_or(&[]),
+    );
+
+    validate_catalog(
+        directory,
+        COMPONENT_SCHEMAS_FILE,
+        &schemas,
+        validate_component_schema,
+    )?;
+    if schemas.is_some() {
+        resolve_schemas(&all_schemas).map_err(|err| {
+            err_msg(format!(
+                "File {:?} contains invalid component schemas: {}",
+                directory.join(COMPONENT_SCHEMAS_FILE),
+                err
+            ))
+        })?;
+    }
+    validate_catalog(directory, COMPONENT_GROUPS_FILE, &groups, |group| {
+        validate_component_group(group, &all_schemas)
+    })?;
+
+    let seeded_catalogs = vec![
+        save_catalog(COMPONENT_GROUPS_FILE, groups, |group| {
+            storage.save_component_group(group)
+        })?,
+        // Previous versions go first, so that they aren't mistaken for the current ones.
+        save_catalog(COMPONENT_SCHEMA_VERSIONS_FILE, schema_versions, |schema| {
+            storage.save_component_schema_version(schema)
+        })?,
+        save_catalog(COMPONENT_SCHEMAS_FILE, schemas, |schema| {
+            let type_name = schema.type_name.clone();
+            let expected_version = stored_versions.get(&type_name).cloned();
+            match storage.save_component_schema(schema, expected_version)? {
+                ReplaceResult::Replaced(_) => Ok(()),
+                _ => Err(err_msg(format!(
+                    "Component schema `{}` has been modified while catalogs were seeded",
+                    type_name
+                ))),
+            }
+        })?,
+        save_catalog(PROJECT_CAPABILITY_GROUPS_FILE, capability_groups, |group| {
+            storage.save_project_capability_group(group)
+        })?,
+        save_catalog(PROJECT_CAPABILITIES_FILE, capabilities, |capability| {
+            storage.save_project_capability(capability)
+        })?,
+        save_catalog(PROJECT_PLATFORMS_FILE, platforms, |platform| {
+            storage.save_project_platform(platform)
+        })?,
+        save_catalog(PROJECT_TEMPLATES_FILE, templates, |template| {
+            storage.save_project_template(template)
+        })?,
+    ];
+
+    Ok(seeded_catalogs
+        .into_iter()
+        .filter_map(|catalog| catalog)
+        .collect())
+}
+
+/// Writes all catalogs from the storage to JSON files in the directory using the same layout
+/// `seed_catalogs` expects, existing files are overwritten. Returns names of the written catalog
+/// files with the number of dumped items. Catalog items that can't be read fail the whole dump.
+///
+/// # Arguments
+///
+/// * `storage` - Storage to read catalogs from.
+/// * `directory` - Directory to write catalog JSON files to, it's created if it doesn't exist.
+pub fn dump_catalogs(
+    storage: &dyn Storage,
+    directory: &Path,
+) -> Result<Vec<(&'static str, usize)>, Error> {
+    fs::create_dir_all(directory)?;
+
+    let schemas = storage.get_component_schemas(ReadMode::Strict)?;
+    let mut schema_versions = vec![];
+    for schema in &schemas {
+        schema_versions.extend(
+            storage
+                .get_component_schema_versions(&schema.type_name, ReadMode::Strict)?
+                .into_iter()
+                .filter(|schema_version| schema_version.version != schema.version),
+        );
+    }
+
+    Ok(vec![
+        dump_catalog(
+            directory,
+            COMPONENT_GROUPS_FILE,
+            storage.get_component_groups(ReadMode::Strict)?,
+        )?,
+        dump_catalog(directory, COMPONENT_SCHEMAS_FILE, schemas)?,
+        dump_catalog(directory, COMPONENT_SCHEMA_VERSIONS_FILE, schema_versions)?,
+        dump_catalog(
+            directory,
+            PROJECT_CAPABILITY_GROUPS_FILE,
+            storage.get_project_capability_groups(ReadMode::Strict)?,
+        )?,
+        dump_catalog(
+            directory,
+            PROJECT_CAPABILITIES_FILE,
+            storage.get_project_capabilities(ReadMode::Strict)?,
+        )?,
+        dump_catalog(
+            directory,
+            PROJECT_PLATFORMS_FILE,
+            storage.get_project_platforms(ReadMode::Strict)?,
+        )?,
+        dump_catalog(
+            directory,
+            PROJECT_TEMPLATES_FILE,
+            storage.get_project_templates(ReadMode::Strict)?,
+        )?,
+    ])
+}
+
+/// Reads all items from the catalog file if it exists, returns `None` otherwise.
+fn read_catalog<T>(directory: &Path, file_name: &str) -> Result<Option<Vec<T>>, Error>
+where
+    T: DeserializeOwned,
+{
+    let path = directory.join(file_name);
+    if path.is_file() {
+        read_json_file(&path).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Validates every item of the catalog that has been read, fails on the first invalid item with
+/// the error that names catalog file and index of the item.
+fn validate_catalog<T, F>(
+    directory: &Path,
+    file_name: &str,
+    items: &Option<Vec<T>>,
+    validate: F,
+) -> Result<(), Error>
+where
+    F: Fn(&T) -> Result<(), ValidationErrors>,
+{
+    let items = match *items {
+        Some(ref items) => items,
+        None => return Ok(()),
+    };
+
+    for (index, item) in items.iter().enumerate() {
+        if let Err(err) = validate(item) {
+            let errors: Vec<String> = err
+                .errors
+                .iter()
+                .map(|error| format!("{}: {}", error.path, error.message))
+                .collect();
+            return Err(err_msg(format!(
+                "File {:?} contains invalid item at index {}: {}",
+                directory.join(file_name),
+                index,
+                errors.join(" ")
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Merges seeded component schemas into the stored ones, seeded schemas replace the stored ones
+/// with the same type.
+fn merge_schemas(
+    mut schemas: Vec<ComponentSchema>,
+    seeded_schemas: &[ComponentSchema],
+) -> Vec<ComponentSchema> {
+    for seeded_schema in seeded_schemas {
+        match schemas
+            .iter_mut()
+            .find(|schema| schema.type_name == seeded_schema.type_name)
+        {
+            Some(schema) => *schema = seeded_schema.clone(),
+            None => schemas.push(seeded_schema.clone()),
+        }
+    }
+
+    schemas
+}
+
+/// Checks seeded previous versions of the component schemas against the stored ones and returns
+/// only versions that aren't stored yet. Stored version with a different content and version of
+/// the schema that is neither stored nor seeded fail the seeding.
+fn new_schema_versions(
+    storage: &dyn Storage,
+    directory: &Path,
+    schema_versions: Vec<ComponentSchema>,
+    stored_schemas: &[ComponentSchema],
+    seeded_schemas: &[ComponentSchema],
+) -> Result<Vec<ComponentSchema>, Error> {
+    let mut new_schema_versions = vec![];
+    for schema_version in schema_versions {
+        let is_known_schema = stored_schemas
+            .iter()
+            .chain(seeded_schemas)
+            .any(|schema| schema.type_name == schema_version.type_name);
+        if !is_known_schema {
+            return Err(err_msg(format!(
+                "File {:?} contains version {} of unknown component schema `{}`",
+                directory.join(COMPONENT_SCHEMA_VERSIONS_FILE),
+                schema_version.version,
+                schema_version.type_name
+            )));
+        }
+
+        match storage
+            .get_component_schema_version(&schema_version.type_name, schema_version.version)?
+        {
+            Some(ref stored_version) if is_same_schema(stored_version, &schema_version) => {}
+            Some(_) => {
+                return Err(err_msg(format!(
+                    "File {:?} contains version {} of component schema `{}` that differs from the \
+                     stored one",
+                    directory.join(COMPONENT_SCHEMA_VERSIONS_FILE),
+                    schema_version.version,
+                    schema_version.type_name
+                )))
+            }
+            None => new_schema_versions.push(schema_version),
+        }
+    }
+
+    Ok(new_schema_versions)
+}
+
+/// Assigns versions to the seeded component schemas, so that reseeding never makes a stale
+/// version current again: schema that is the same as the stored one keeps stored version, while
+/// changed schema gets version that is greater than any stored or seeded version of it.
+fn assign_schema_versions(
+    storage: &dyn Storage,
+    schemas: &mut [ComponentSchema],
+    stored_schemas: &[ComponentSchema],
+    schema_versions: &[ComponentSchema],
+) -> Result<(), Error> {
+    for schema in schemas {
+        let stored_schema = stored_schemas
+            .iter()
+            .find(|stored_schema| stored_schema.type_name == schema.type_name);
+        if let Some(stored_schema) = stored_schema {
+            if is_same_schema(stored_schema, schema) {
+                schema.version = stored_schema.version;
+                continue;
+            }
+        }
+
+        let latest_version = storage
+            .get_component_schema_versions(&schema.type_name, ReadMode::Strict)?
+            .iter()
+            .chain(schema_versions)
+            .filter(|schema_version| schema_version.type_name == schema.type_name)
+            .map(|schema_version| schema_version.version)
+            .max();
+        if let Some(latest_version) = latest_version {
+            schema.version = cmp::max(schema.version, latest_version + 1);
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks whether component schemas are the same apart from their versions.
+fn is_same_schema(schema: &ComponentSchema, other_schema: &ComponentSchema) -> bool {
+    let mut other_schema = other_schema.clone();
+    other_schema.version = schema.version;
+    serde_json::to_value(schema).ok() == serde_json::to_value(other_schema).ok()
+}
+
+/// Saves all items of the catalog that has been read, returns `None` if there is no catalog.
+fn save_catalog<T, F>(
+    file_name: &'static str,
+    items: Option<Vec<T>>,
+    save: F,
+) -> Result<Option<(&'static str, usize)>, Error>
+where
+    F: Fn(T) -> Result<(), Error>,
+{
+    let items = match items {
+        Some(items) => items,
+        None => return Ok(None),
+    };
+
+    let items_count = items.len();
+    for item in items {
+        save(item)?;
+    }
+
+    Ok(Some((file_name, items_count)))
+}
+
+fn dump_catalog<T>(
+    directory: &Path,
+    file_name: &'static str,
+    items: Vec<T>,
+) -> Result<(&'static str, usize), Error>
+where
+    T: Serialize,
+{
+    serde_json::to_writer_pretty(File::create(directory.join(file_name))?, &items)?;
+    Ok((file_name, items.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dump_catalogs, read_json_file, seed_catalogs, seed_project_templates};
+    use components::component_schema::ComponentSchema;
+    use db::memory_storage::MemoryStorage;
+    use db::storage::{ReadMode, Storage};
+    use std::env;
+    use std::fs;
+    use uuid::Uuid;
+
+    #[test]
+    fn templates_should_be_seeded_from_directory() {
+        let directory = env::temp_dir().join(format!("frunze-seed-{}", Uuid::new_v4()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("a.json"),
+            r#"[
+                {"id": "blink", "name": "Blink", "description": "", "capabilities": [], "platform": "arduino"},
+                {"id": "bell", "name": "Bell", "description": "", "capabilities": [], "platform": "arduino"}
+            ]"#,
+        ).unwrap();
+        fs::write(
+            directory.join("b.json"),
+            r#"{"id": "blink", "name": "Blink v2", "description": "", "capabilities": [], "platform": "arduino"}"#,
+        ).unwrap();
+        fs::write(directory.join("readme.txt"), "not a template").unwrap();
+
+        let storage = MemoryStorage::new();
+        let seeded_templates = seed_project_templates(&storage, &directory);
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(seeded_templates.unwrap(), 3);
+        assert_eq!(
+            storage
+                .get_project_templates(ReadMode::Strict)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            storage.get_project_template("blink").unwrap().unwrap().name,
+            "Blink v2"
+        );
+    }
+
+    #[test]
+    fn catalogs_should_be_seeded_and_dumped() {
+        let directory = env::temp_dir().join(format!("frunze-catalogs-{}", Uuid::new_v4()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("component_schemas.json"),
+            r#"[{"type": "led", "name": "LED", "description": "", "mpn": null, "properties": {}, "actions": {}, "triggers": {}}]"#,
+        ).unwrap();
+        fs::write(
+            directory.join("project_platforms.json"),
+            r#"[{"type": "arduino", "name": "Arduino", "description": "", "capabilities": []}]"#,
+        ).unwrap();
+
+        let storage = MemoryStorage::new();
+        let dump_directory = directory.join("dump");
+        let seeded_catalogs =
+            seed_catalogs(&storage, &directory).and_then(|_| seed_catalogs(&storage, &directory));
+        let dumped_catalogs = dump_catalogs(&storage, &dump_directory);
+        let dumped_schemas: Vec<ComponentSchema> =
+            read_json_file(&dump_directory.join("component_schemas.json")).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        // Seeding is idempotent.
+        assert_eq!(
+            seeded_catalogs.unwrap(),
+            vec![("component_schemas.json", 1), ("project_platforms.json", 1)]
+        );
+        assert_eq!(
+            storage
+                .get_component_schemas(ReadMode::Strict)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            storage
+                .get_project_platforms(ReadMode::Strict)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let dumped_catalogs = dumped_catalogs.unwrap();
+        assert_eq!(dumped_catalogs.len(), 7);
+        assert!(dumped_catalogs.contains(&("component_groups.json", 0)));
+        assert_eq!(dumped_schemas.len(), 1);
+        assert_eq!(dumped_schemas[0].type_name, "led");
+    }
+
+    #[test]
+    fn seeded_schemas_should_be_versioned() {
+        let directory = env::temp_dir().join(format!("frunze-catalogs-{}", Uuid::new_v4()));
+        let dump_directory = directory.join("dump");
+        fs::create_dir_all(&directory).unwrap();
+        let write_schemas = |name: &str| {
+            fs::write(
+                directory.join("component_schemas.json"),
+                format!(
+                    r#"[{{"type": "led", "name": "{}", "description": "", "mpn": null, "properties": {{}}, "actions": {{}}, "triggers": {{}}}}]"#,
+                    name
+                ),
+            ).unwrap();
+        };
+
+        let storage = MemoryStorage::new();
+        write_schemas("LED");
+        seed_catalogs(&storage, &directory).unwrap();
+        write_schemas("Light");
+        seed_catalogs(&storage, &directory).unwrap();
+        seed_catalogs(&storage, &directory).unwrap();
+        // Stale schema doesn't become current again.
+        write_schemas("LED");
+        seed_catalogs(&storage, &directory).unwrap();
+
+        let dumped_catalogs = dump_catalogs(&storage, &dump_directory);
+        let restored_storage = MemoryStorage::new();
+        let restored_catalogs = seed_catalogs(&restored_storage, &dump_directory);
+        fs::remove_dir_all(&directory).unwrap();
+
+        let versions = |storage: &MemoryStorage| {
+            storage
+                .get_component_schema_versions("led", ReadMode::Strict)
+                .unwrap()
+                .into_iter()
+                .map(|schema| (schema.version, schema.name))
+                .collect::<Vec<_>>()
+        };
+        let expected_versions = vec![
+            (3, "LED".to_string()),
+            (2, "Light".to_string()),
+            (1, "LED".to_string()),
+        ];
+        assert_eq!(versions(&storage), expected_versions);
+
+        // Previous versions survive dump and seed round trip.
+        assert!(dumped_catalogs
+            .unwrap()
+            .contains(&("component_schema_versions.json", 2)));
+        assert!(restored_catalogs
+            .unwrap()
+            .contains(&("component_schema_versions.json", 2)));
+        assert_eq!(versions(&restored_storage), expected_versions);
+    }
+
+    #[test]
+    fn catalogs_with_unresolvable_schemas_should_not_be_seeded() {
+        let directory = env::temp_dir().join(format!("frunze-catalogs-{}", Uuid::new_v4()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("component_schemas.json"),
+            r#"[{"type": "led", "name": "LED", "description": "", "mpn": null, "properties": {}, "actions": {}, "triggers": {}, "extends": "light"}]"#,
+        ).unwrap();
+        fs::write(
+            directory.join("project_platforms.json"),
+            r#"[{"type": "arduino", "name": "Arduino", "description": "", "capabilities": []}]"#,
+        ).unwrap();
+
+        let storage = MemoryStorage::new();
+        let seeded_catalogs = seed_catalogs(&storage, &directory);
+        fs::remove_dir_all(&directory).unwrap();
+
+        let err = seeded_catalogs.unwrap_err().to_string();
+        assert!(err.contains("component_schemas.json"));
+        assert!(err.contains("unknown schema `light`"));
+
+        // Nothing is saved if any of the catalogs is invalid.
+        assert!(storage
+            .get_component_schemas(ReadMode::Strict)
+            .unwrap()
+            .is_empty());
+        assert!(storage
+            .get_project_platforms(ReadMode::Strict)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn catalogs_with_invalid_items_should_not_be_seeded() {
+        let directory = env::temp_dir().join(format!("frunze-catalogs-{}", Uuid::new_v4()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("component_schemas.json"),
+            r#"[{"type": "led", "name": "LED", "description": "", "mpn": null, "properties": {}, "actions": {}, "triggers": {}}]"#,
+        ).unwrap();
+        fs::write(
+            directory.join("component_groups.json"),
+            r#"[
+                {"type": "basic", "name": "Basic", "description": "", "items": ["led"]},
+                {"type": "motors", "name": "Motors", "description": "", "items": ["servo"]}
+            ]"#,
+        ).unwrap();
+
+        let storage = MemoryStorage::new();
+        let seeded_catalogs = seed_catalogs(&storage, &directory);
+        fs::remove_dir_all(&directory).unwrap();
+
+        let err = seeded_catalogs.unwrap_err().to_string();
+        assert!(err.contains("component_groups.json"));
+        assert!(err.contains("index 1"));
+        assert!(err.contains("Unknown component type `servo`."));
+
+        assert!(storage
+            .get_component_schemas(ReadMode::Strict)
+            .unwrap()
+            .is_empty());
+        assert!(storage
+            .get_component_groups(ReadMode::Strict)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+use failure::{err_msg, Error};
+
+use super::migrations::AppliedMigration;
+use components::component_group::ComponentGroup;
+use components::component_schema::ComponentSchema;
+use projects::project::Project;
+use projects::project_capability::ProjectCapability;
+use projects::project_capability_group::ProjectCapabilityGroup;
+use projects::project_platform::ProjectPlatform;
+use projects::project_query::ProjectQuery;
+use projects::project_revision::{ProjectChange, ProjectRevision};
+use projects::project_template::ProjectTemplate;
+use projects::trashed_project::TrashedProject;
+use utils::current_timestamp;
+
+/// Result of the conditional replace operation.
+#[derive(Debug)]
+pub enum ReplaceResult<T> {
+    /// Entity has been successfully replaced, contains the saved entity.
+    Replaced(T),
+    /// There is no entity to replace.
+    NotFound,
+    /// Entity has been modified since the expected revision, contains the current revision.
+    Conflict(i64),
+}
+
+/// Single page of the entities matching the query.
+#[derive(Debug)]
+pub struct Page<T> {
+    /// Entities that belong to the requested page.
+    pub items: Vec<T>,
+    /// Total number of the entities matching the query.
+    pub total: u64,
+}
+
+/// Describes how stored documents that can't be read are handled, every method that reads whole
+/// collection (or its part) accepts the mode to use.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReadMode {
+    /// Whole operation fails.
+    Strict,
+    /// Document is skipped, operation returns all other documents.
+    Lenient,
+}
+
+/// Describes stored document that couldn't be read.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReadFailure {
+    /// Name of the collection the document belongs to.
+    pub collection: String,
+    /// Identifier of the document, if known.
+    #[serde(rename(serialize = "documentId", deserialize = "documentId"))]
+    pub document_id: Option<String>,
+    /// Description of the error.
+    pub error: String,
+    /// Number of milliseconds elapsed since Unix epoch when document was last failed to be read.
+    #[serde(rename(serialize = "occurredAt", deserialize = "occurredAt"))]
+    pub occurred_at: i64,
+}
+
+/// Collects items read from the collection. Every document comes with its identifier (if known)
+/// and the result of reading it, documents that can't be read are logged and passed to
+/// `on_failure` to be recorded, then either skipped or returned as an error depending on the
+/// `read_mode`.
+pub fn read_documents<T, I, F>(
+    collection_name: &str,
+    documents: I,
+    read_mode: ReadMode,
+    mut on_failure: F,
+) -> Result<Vec<T>, Error>
+where
+    I: IntoIterator<Item = (Option<String>, Result<T, Error>)>,
+    F: FnMut(ReadFailure),
+{
+    let mut items = vec![];
+    for (document_id, item) in documents {
+        let err = match item {
+            Ok(item) => {
+                items.push(item);
+                continue;
+            }
+            Err(err) => err,
+        };
+
+        let document_name = document_id
+            .clone()
+            .unwrap_or_else(|| "<unknown>".to_string());
+        warn!(
+            "Failed to read document {} from `{}`: {}",
+            document_name, collection_name, err
+        );
+
+        on_failure(ReadFailure {
+            collection: collection_name.to_string(),
+            document_id,
+            error: err.to_string(),
+            occurred_at: current_timestamp(),
+        });
+
+        if read_mode == ReadMode::Strict {
+            return Err(err_msg(format!(
+                "Document {} from `{}` can't be read: {}",
+                document_name, collection_name, err
+            )));
+        }
+    }
+
+    Ok(items)
+}
+
+/// Describes all operations that storage backend should support.
+pub trait Storage {
+    /// Queries project instance from the storage using passed `project_id`.
+    fn get_project(&self, project_id: &str) -> Result<Option<Project>, Error>;
+
+    /// Moves project with the passed `project_id` to the trash, project revisions are kept until
+    /// project is purged. Returns `false` if there was no project with such identifier.
+    fn trash_project(&self, project_id: &str, change: ProjectChange) -> Result<bool, Error>;
+
+    /// Queries all projects from the trash, the most recently deleted projects go first.
+    fn get_trashed_projects(&self, read_mode: ReadMode) -> Result<Vec<TrashedProject>, Error>;
+
+    /// Moves project with the passed `project_id` from the trash back to the regular projects.
+    /// Returns `None` if there was no such project in the trash.
+    fn restore_trashed_project(&self, project_id: &str) -> Result<Option<Project>, Error>;
+
+    /// Permanently deletes project with the passed `project_id` and all its revisions from the
+    /// trash. Returns `false` if there was no such project in the trash.
+    fn purge_trashed_project(&self, project_id: &str) -> Result<bool, Error>;
+
+    /// Permanently deletes all projects that were moved to the trash before `deleted_before`
+    /// timestamp. Returns identifiers of the purged projects.
+    fn purge_trashed_projects(&self, deleted_before: i64) -> Result<Vec<String>, Error>;
+
+    /// Queries all projects from the storage.
+    fn get_projects(&self, read_mode: ReadMode) -> Result<Vec<Project>, Error>;
+
+    /// Queries projects that may match the search `query` (see `project_search::search`), so
+    /// that only those are ranked. Storage may return projects that don't match the query, but
+    /// never skips the matching ones.
+    fn search_projects(&self, query: &str, read_mode: ReadMode) -> Result<Vec<Project>, Error>;
+
+    /// Queries single page of the projects that match the `query`.
+    fn find_projects(
+        &self,
+        query: &ProjectQuery,
+        read_mode: ReadMode,
+    ) -> Result<Page<Project>, Error>;
+
+    /// Inserts new project into the storage, project identifier is generated by the storage
+    /// unless it's already set (e.g. for imported projects), revision is reset to `1` and
+    /// timestamps and authorship are set according to the `change`. Initial project revision is
+    /// recorded as well. Returns `None` if project with the same identifier already exists.
+    fn insert_project(
+        &self,
+        project: Project,
+        change: ProjectChange,
+    ) -> Result<Option<Project>, Error>;
+
+    /// Replaces existing project with the same identifier only if its current revision is equal
+    /// to `expected_revision`. Revision of the saved project is incremented and recorded, creation
+    /// timestamp and author are preserved, while update ones are set according to the `change`.
+    fn replace_project(
+        &self,
+        project: Project,
+        expected_revision: i64,
+        change: ProjectChange,
+    ) -> Result<ReplaceResult<Project>, Error>;
+
+    /// Queries all recorded revisions of the project, the most recent revisions go first.
+    fn get_project_revisions(
+        &self,
+        project_id: &str,
+        read_mode: ReadMode,
+    ) -> Result<Vec<ProjectRevision>, Error>;
+
+    /// Queries specific revision of the project.
+    fn get_project_revision(
+        &self,
+        project_id: &str,
+        revision: i64,
+    ) -> Result<Option<ProjectRevision>, Error>;
+
+    /// Queries component groups from the storage.
+    fn get_component_groups(&self, read_mode: ReadMode) -> Result<Vec<ComponentGroup>, Error>;
+
+    /// Queries component schemas from the storage.
+    fn get_component_schemas(&self, read_mode: ReadMode) -> Result<Vec<ComponentSchema>, Error>;
+
+    /// Inserts component group into the storage or replaces existing one with the same type.
+    fn save_component_group(&self, group: ComponentGroup) -> Result<(), Error>;
+
+    /// Queries all known versions of the component schema with the passed `type_name`, the most
+    /// recent versions go first.
+    fn get_component_schema_versions(
+        &self,
+        type_name: &str,
+        read_mode: ReadMode,
+    ) -> Result<Vec<ComponentSchema>, Error>;
+
+    /// Queries specific version of the component schema, it can be either the current version or
+    /// one of the previous ones.
+    fn get_component_schema_version(
+        &self,
+        type_name: &str,
+        version: i64,
+    ) -> Result<Option<ComponentSchema>, Error>;
+
+    /// Inserts component schema into the storage if `expected_version` is `None`, or replaces
+    /// existing one with the same type only if its current version is equal to
+    /// `expected_version`. If replaced schema has a different version, it's kept as one of the
+    /// previous versions, so that components authored against it can still be read. Conflict
+    /// contains the version of the schema that has been saved concurrently.
+    fn save_component_schema(
+        &self,
+        schema: ComponentSchema,
+        expected_version: Option<i64>,
+    ) -> Result<ReplaceResult<ComponentSchema>, Error>;
+
+    /// Inserts one of the previous versions of the component schema or replaces existing one with
+    /// the same type and version, current version of the schema isn't affected.
+    fn save_component_schema_version(&self, schema: ComponentSchema) -> Result<(), Error>;
+
+    /// Deletes component group with the passed `type_name`. Returns `false` if there was no such
+    /// group.
+    fn delete_component_group(&self, type_name: &str) -> Result<bool, Error>;
+
+    /// Deletes component schema with the passed `type_name` along with all its previous versions.
+    /// Returns `false` if there was no such schema.
+    fn delete_component_schema(&self, type_name: &str) -> Result<bool, Error>;
+
+    /// Queries sorted identifiers of the projects (including trashed ones) that have at least one
+    /// component of the passed `type_name`.
+    fn get_component_type_usages(&self, type_name: &str) -> Result<Vec<String>, Error>;
+
+    /// Queries project capability groups from the storage.
+    fn get_project_capability_groups(
+        &self,
+        read_mode: ReadMode,
+    ) -> Result<Vec<ProjectCapabilityGroup>, Error>;
+
+    /// Queries all known project capabilities from the storage.
+    fn get_project_capabilities(
+        &self,
+        read_mode: ReadMode,
+    ) -> Result<Vec<ProjectCapability>, Error>;
+
+    /// Queries all known project platforms from the storage.
+    fn get_project_platforms(&self, read_mode: ReadMode) -> Result<Vec<ProjectPlatform>, Error>;
+
+    /// Inserts project capability group into the storage or replaces existing one with the same
+    /// type.
+    fn save_project_capability_group(&self, group: ProjectCapabilityGroup) -> Result<(), Error>;
+
+    /// Inserts project capability into the storage or replaces existing one with the same type.
+    fn save_project_capability(&self, capability: ProjectCapability) -> Result<(), Error>;
+
+    /// Inserts project platform into the storage or replaces existing one with the same type.
+    fn save_project_platform(&self, platform: ProjectPlatform) -> Result<(), Error>;
+
+    /// Queries all project templates from the storage.
+    fn get_project_templates(&self, read_mode: ReadMode) -> Result<Vec<ProjectTemplate>, Error>;
+
+    /// Queries project template instance from the storage using passed `template_id`.
+    fn get_project_template(&self, template_id: &str) -> Result<Option<ProjectTemplate>, Error>;
+
+    /// Inserts project template into the storage or replaces existing one with the same
+    /// identifier.
+    fn save_project_template(&self, template: ProjectTemplate) -> Result<(), Error>;
+
+    /// Brings structure of the storage up to date (e.g. creates missing indexes) and eagerly
+    /// upgrades all stored documents with the migrations that haven't been applied yet, it's safe
+    /// to run it multiple times. Returns migrations applied during this run.
+    fn migrate(&self) -> Result<Vec<AppliedMigration>, Error>;
+
+    /// Queries stored documents that failed to be read, only the latest failure is kept for
+    /// every document. The most recent failures go first.
+    fn get_read_failures(&self) -> Result<Vec<ReadFailure>, Error>;
+
+    /// Forgets all recorded read failures.
+    fn clear_read_failures(&self) -> Result<(), Error>;
+}
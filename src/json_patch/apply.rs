@@ -0,0 +1,177 @@
+use serde_json::Value;
+
+use super::patch_error::PatchError;
+use super::patch_operation::PatchOperation;
+use super::pointer::split_last;
+
+/// Applies JSON Patch operations to the document, see
+/// [RFC 6902](https://tools.ietf.org/html/rfc6902). Patch is applied atomically: if any of the
+/// operations fails, error is returned and no changes are visible to the caller.
+///
+/// # Arguments
+///
+/// * `document` - JSON document to apply patch to.
+/// * `operations` - List of the patch operations to apply.
+pub fn apply(document: &Value, operations: &[PatchOperation]) -> Result<Value, PatchError> {
+    let mut patched_document = document.clone();
+    for (index, operation) in operations.iter().enumerate() {
+        apply_operation(&mut patched_document, operation)
+            .map_err(|message| PatchError { index, message })?;
+    }
+
+    Ok(patched_document)
+}
+
+fn apply_operation(document: &mut Value, operation: &PatchOperation) -> Result<(), String> {
+    match operation {
+        PatchOperation::Add { path, value } => add(document, path, value.clone()),
+        PatchOperation::Remove { path } => remove(document, path).map(|_| ()),
+        PatchOperation::Replace { path, value } => {
+            let target = document
+                .pointer_mut(path)
+                .ok_or_else(|| format!("Path `{}` doesn't exist.", path))?;
+            *target = value.clone();
+            Ok(())
+        }
+        PatchOperation::Move { from, path } => {
+            if path.starts_with(&format!("{}/", from)) {
+                return Err(format!(
+                    "Value at `{}` can't be moved to its own child `{}`.",
+                    from, path
+                ));
+            }
+
+            let value = remove(document, from)?;
+            add(document, path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = document
+                .pointer(from)
+                .cloned()
+                .ok_or_else(|| format!("Path `{}` doesn't exist.", from))?;
+            add(document, path, value)
+        }
+        PatchOperation::Test { path, value } => {
+            if document.pointer(path) == Some(value) {
+                Ok(())
+            } else {
+                Err(format!("Value at `{}` is not equal to the expected one.", path))
+            }
+        }
+    }
+}
+
+/// Parses array index reference token, index should be less than `upper_bound`.
+fn parse_index(token: &str, upper_bound: usize) -> Result<usize, String> {
+    let is_valid = !token.is_empty()
+        && token.chars().all(|c| c.is_ascii_digit())
+        && (token == "0" || !token.starts_with('0'));
+
+    match token.parse::<usize>() {
+        Ok(index) if is_valid && index < upper_bound => Ok(index),
+        _ => Err(format!("Array index `{}` is not valid.", token)),
+    }
+}
+
+fn add(document: &mut Value, path: &str, value: Value) -> Result<(), String> {
+    if path.is_empty() {
+        *document = value;
+        return Ok(());
+    }
+
+    let (parent_path, token) =
+        split_last(path).ok_or_else(|| format!("Path `{}` is not valid.", path))?;
+    match document.pointer_mut(parent_path) {
+        Some(Value::Object(map)) => {
+            map.insert(token, value);
+            Ok(())
+        }
+        Some(Value::Array(items)) => {
+            let index = if token == "-" {
+                items.len()
+            } else {
+                parse_index(&token, items.len() + 1)?
+            };
+            items.insert(index, value);
+            Ok(())
+        }
+        Some(_) => Err(format!("Value at `{}` is not a container.", parent_path)),
+        None => Err(format!("Path `{}` doesn't exist.", parent_path)),
+    }
+}
+
+fn remove(document: &mut Value, path: &str) -> Result<Value, String> {
+    let (parent_path, token) =
+        split_last(path).ok_or_else(|| format!("Path `{}` can't be removed.", path))?;
+    match document.pointer_mut(parent_path) {
+        Some(Value::Object(map)) => map
+            .remove(&token)
+            .ok_or_else(|| format!("Path `{}` doesn't exist.", path)),
+        Some(Value::Array(items)) => {
+            let index = parse_index(&token, items.len())?;
+            Ok(items.remove(index))
+        }
+        _ => Err(format!("Path `{}` doesn't exist.", path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply;
+    use json_patch::patch_operation::PatchOperation;
+
+    fn operations(value: ::serde_json::Value) -> Vec<PatchOperation> {
+        ::serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn apply_should_support_all_operations() {
+        let document = json!({
+            "name": "project",
+            "components": [{ "id": "a" }, { "id": "b" }],
+            "tags": { "x/y": 1 }
+        });
+
+        let patch = operations(json!([
+            { "op": "test", "path": "/name", "value": "project" },
+            { "op": "replace", "path": "/name", "value": "renamed" },
+            { "op": "add", "path": "/components/-", "value": { "id": "c" } },
+            { "op": "add", "path": "/components/0", "value": { "id": "z" } },
+            { "op": "remove", "path": "/components/1" },
+            { "op": "copy", "from": "/tags/x~1y", "path": "/tags/copy" },
+            { "op": "move", "from": "/tags/x~1y", "path": "/moved" }
+        ]));
+
+        assert_eq!(
+            apply(&document, &patch).unwrap(),
+            json!({
+                "name": "renamed",
+                "components": [{ "id": "z" }, { "id": "b" }, { "id": "c" }],
+                "tags": { "copy": 1 },
+                "moved": 1
+            })
+        );
+    }
+
+    #[test]
+    fn apply_should_fail_atomically() {
+        let document = json!({ "name": "project", "components": [] });
+
+        let patch = operations(json!([
+            { "op": "replace", "path": "/name", "value": "renamed" },
+            { "op": "test", "path": "/name", "value": "project" }
+        ]));
+        let error = apply(&document, &patch).unwrap_err();
+        assert_eq!(error.index, 1);
+
+        let patch = operations(json!([
+            { "op": "remove", "path": "/components/0" }
+        ]));
+        assert_eq!(apply(&document, &patch).unwrap_err().index, 0);
+
+        let patch = operations(json!([
+            { "op": "add", "path": "/components/01", "value": 1 }
+        ]));
+        assert_eq!(apply(&document, &patch).unwrap_err().index, 0);
+    }
+}
@@ -0,0 +1,128 @@
+use serde_json::Value;
+
+use super::patch_operation::PatchOperation;
+use super::pointer::escape_token;
+
+/// Builds the list of JSON Patch operations that transforms `from` value into `to` value.
+///
+/// # Arguments
+///
+/// * `from` - Value to calculate diff from.
+/// * `to` - Value to calculate diff to.
+pub fn diff(from: &Value, to: &Value) -> Vec<PatchOperation> {
+    let mut operations = Vec::new();
+    diff_at("", from, to, &mut operations);
+    operations
+}
+
+fn diff_at(path: &str, from: &Value, to: &Value, operations: &mut Vec<PatchOperation>) {
+    match (from, to) {
+        (Value::Object(from_map), Value::Object(to_map)) => {
+            for (key, from_value) in from_map {
+                let key_path = format!("{}/{}", path, escape_token(key));
+                match to_map.get(key) {
+                    Some(to_value) => diff_at(&key_path, from_value, to_value, operations),
+                    None => operations.push(PatchOperation::Remove { path: key_path }),
+                }
+            }
+
+            for (key, to_value) in to_map {
+                if !from_map.contains_key(key) {
+                    operations.push(PatchOperation::Add {
+                        path: format!("{}/{}", path, escape_token(key)),
+                        value: to_value.clone(),
+                    });
+                }
+            }
+        }
+        (Value::Array(from_items), Value::Array(to_items)) => {
+            let common_length = from_items.len().min(to_items.len());
+            for index in 0..common_length {
+                diff_at(
+                    &format!("{}/{}", path, index),
+                    &from_items[index],
+                    &to_items[index],
+                    operations,
+                );
+            }
+
+            // Remove redundant items starting from the end so that indices stay valid.
+            for index in (common_length..from_items.len()).rev() {
+                operations.push(PatchOperation::Remove {
+                    path: format!("{}/{}", path, index),
+                });
+            }
+
+            for (index, to_value) in to_items.iter().enumerate().skip(common_length) {
+                operations.push(PatchOperation::Add {
+                    path: format!("{}/{}", path, index),
+                    value: to_value.clone(),
+                });
+            }
+        }
+        (from, to) => {
+            if from != to {
+                operations.push(PatchOperation::Replace {
+                    path: path.to_string(),
+                    value: to.clone(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff;
+    use json_patch::patch_operation::PatchOperation;
+
+    #[test]
+    fn diff_should_describe_all_changes() {
+        let from = json!({
+            "name": "one",
+            "removed": true,
+            "items": [1, 2, 3],
+            "nested": { "a/b": 1 }
+        });
+        let to = json!({
+            "name": "two",
+            "items": [1, 5],
+            "nested": { "a/b": 2 },
+            "added": null
+        });
+
+        assert_eq!(
+            diff(&from, &to),
+            vec![
+                PatchOperation::Replace {
+                    path: "/items/1".to_string(),
+                    value: json!(5),
+                },
+                PatchOperation::Remove {
+                    path: "/items/2".to_string(),
+                },
+                PatchOperation::Replace {
+                    path: "/name".to_string(),
+                    value: json!("two"),
+                },
+                PatchOperation::Replace {
+                    path: "/nested/a~1b".to_string(),
+                    value: json!(2),
+                },
+                PatchOperation::Remove {
+                    path: "/removed".to_string(),
+                },
+                PatchOperation::Add {
+                    path: "/added".to_string(),
+                    value: json!(null),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_of_equal_values_should_be_empty() {
+        let value = json!({ "components": [{ "id": "1" }] });
+        assert!(diff(&value, &value).is_empty());
+    }
+}
@@ -0,0 +1,63 @@
+use serde_json::{Map, Value};
+
+/// Applies JSON Merge Patch to the target value and returns the result, see
+/// [RFC 7396](https://tools.ietf.org/html/rfc7396).
+///
+/// # Arguments
+///
+/// * `target` - Value to apply patch to.
+/// * `patch` - Merge patch document.
+pub fn merge(target: &Value, patch: &Value) -> Value {
+    match patch {
+        Value::Object(patch_map) => {
+            let mut result = match target {
+                Value::Object(target_map) => target_map.clone(),
+                _ => Map::new(),
+            };
+
+            for (key, patch_value) in patch_map {
+                if patch_value.is_null() {
+                    result.remove(key);
+                } else {
+                    let merged_value = merge(result.get(key).unwrap_or(&Value::Null), patch_value);
+                    result.insert(key.clone(), merged_value);
+                }
+            }
+
+            Value::Object(result)
+        }
+        _ => patch.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge;
+
+    #[test]
+    fn merge_should_follow_rfc() {
+        let target = json!({
+            "title": "Goodbye!",
+            "author": { "givenName": "John", "familyName": "Doe" },
+            "tags": ["example", "sample"],
+            "content": "This will be unchanged"
+        });
+        let patch = json!({
+            "title": "Hello!",
+            "phoneNumber": "+01-123-456-7890",
+            "author": { "familyName": null },
+            "tags": ["example"]
+        });
+
+        assert_eq!(
+            merge(&target, &patch),
+            json!({
+                "title": "Hello!",
+                "author": { "givenName": "John" },
+                "tags": ["example"],
+                "content": "This will be unchanged",
+                "phoneNumber": "+01-123-456-7890"
+            })
+        );
+    }
+}
@@ -0,0 +1,6 @@
+pub mod apply;
+pub mod diff;
+pub mod merge_patch;
+pub mod patch_error;
+pub mod patch_operation;
+pub mod pointer;
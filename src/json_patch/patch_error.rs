@@ -0,0 +1,20 @@
+use std::fmt;
+
+use failure::Fail;
+
+/// Describes failure of the JSON Patch operation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchError {
+    /// Index of the failed operation within the patch.
+    pub index: usize,
+    /// Human-readable description of the failure.
+    pub message: String,
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Patch operation {} failed: {}", self.index, self.message)
+    }
+}
+
+impl Fail for PatchError {}
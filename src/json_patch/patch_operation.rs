@@ -0,0 +1,19 @@
+use serde_json::Value;
+
+/// Describes single JSON Patch operation, see [RFC 6902](https://tools.ietf.org/html/rfc6902).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    /// Adds a value to an object or inserts it into an array.
+    Add { path: String, value: Value },
+    /// Removes the value at the target location.
+    Remove { path: String },
+    /// Replaces the value at the target location with a new value.
+    Replace { path: String, value: Value },
+    /// Removes the value at `from` location and adds it to the target location.
+    Move { from: String, path: String },
+    /// Copies the value at `from` location to the target location.
+    Copy { from: String, path: String },
+    /// Tests that the value at the target location is equal to a specified value.
+    Test { path: String, value: Value },
+}
@@ -0,0 +1,18 @@
+/// Escapes single JSON Pointer reference token, see
+/// [RFC 6901](https://tools.ietf.org/html/rfc6901#section-3).
+pub fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Unescapes single JSON Pointer reference token.
+pub fn unescape_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Splits non-root JSON Pointer into pointer to the parent value and unescaped last reference
+/// token. Returns `None` if pointer is not valid.
+pub fn split_last(pointer: &str) -> Option<(&str, String)> {
+    pointer
+        .rfind('/')
+        .map(|index| (&pointer[..index], unescape_token(&pointer[index + 1..])))
+}
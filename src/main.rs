@@ -12,38 +12,50 @@ extern crate mongodb;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
+#[macro_use]
 extern crate serde_json;
 extern crate url;
 extern crate uuid;
 extern crate zip;
 
-use bytes::Bytes;
-use std::path::PathBuf;
-
-use actix_web::{
-    fs::NamedFile, http, middleware::cors::Cors, middleware::cors::CorsBuilder, server, App,
-    HttpRequest, HttpResponse, Json, Result, State,
-};
+use actix_web::server;
 use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::process;
 
 mod bom;
 mod components;
 mod db;
+mod json_patch;
 mod projects;
+mod routes;
 mod schematic;
+#[cfg(test)]
+mod test_fixtures;
+mod utils;
+mod validation;
 
 use bom::bom_provider::BomProvider;
-use db::DB;
+use db::memory_storage::MemoryStorage;
+use db::mongo_storage::MongoStorage;
+use db::seed::{dump_catalogs, seed_catalogs, seed_project_templates};
+use db::storage::Storage;
 use docopt::Docopt;
-use failure::Error;
+use routes::app_state::AppState;
+use routes::create_app;
 use schematic::schematic_provider::SchematicProvider;
 
 use url::Url;
 
 const USAGE: &str = "
 Usage: frunze_api [--verbose] [--ip=<address>] [--port=<port>] [--db-ip=<address>]
-                  [--db-port=<port>] [--db-name=<name>] [--bom-api-url=<url>]
-                  [--bom-api-key=<key>] [--export-api-url=<url>]
+                  [--db-port=<port>] [--db-name=<name>] [--storage=<kind>]
+                  [--bom-api-url=<url>] [--bom-api-key=<key>] [--export-api-url=<url>]
+                  [--trash-retention=<days>] [--catalogs-dir=<dir>]
+                  [--templates-dir=<dir>] [--admin-token=<token>]
+       frunze_api seed <dir> [--db-ip=<address>] [--db-port=<port>] [--db-name=<name>]
+       frunze_api dump <dir> [--db-ip=<address>] [--db-port=<port>] [--db-name=<name>]
+       frunze_api migrate [--db-ip=<address>] [--db-port=<port>] [--db-name=<name>]
        frunze_api --help
 Options:
     --ip <ip>               IP (v4) address to listen on [default: 0.0.0.0].
@@ -51,156 +63,56 @@ Options:
     --db-ip <ip>            IP (v4) address of the database [default: 0.0.0.0].
     --db-port <port>        Port number of the database [default: 27017].
     --db-name <name>        Name of the database to use [default: frunze].
+    --storage <kind>        Storage to use, either `mongodb` or `memory` [default: mongodb].
     --bom-api-url <url>     URL of BOM API provider [default: http://octopart.com/api/v3].
     --bom-api-key <key>     API key to use for all requests to BOM API provider.
     --export-api-url <url>  URL of Schematic Export API provider [default: http://localhost:8010].
+    --trash-retention <days>  Number of days deleted projects are kept in the trash [default: 30].
+    --catalogs-dir <dir>    Directory with catalog JSON files to seed on start, the same as for
+                            `seed` command (e.g. to fill in-memory storage).
+    --templates-dir <dir>   Directory with project template JSON files to seed on start.
+    --admin-token <token>   Token that grants access to the catalog administration API, the API
+                            is disabled if it's not set.
     --verbose               Toggle verbose output.
     --help                  Print this help menu.
+Commands:
+    seed <dir>              Migrate the database and load catalogs from JSON files in the
+                            directory, catalog items with the same type are replaced.
+    dump <dir>              Export catalogs from the database to JSON files in the directory.
+    migrate                 Bring structure of the database up to date and upgrade all stored
+                            documents with the pending migrations.
 ";
 
 #[derive(Debug, Deserialize)]
 struct Args {
+    cmd_seed: bool,
+    cmd_dump: bool,
+    cmd_migrate: bool,
+    arg_dir: Option<String>,
     flag_ip: Option<String>,
     flag_port: Option<u16>,
     flag_db_ip: Option<String>,
     flag_db_port: Option<u16>,
     flag_db_name: Option<String>,
+    flag_storage: Option<String>,
     flag_bom_api_url: Option<String>,
     flag_bom_api_key: String,
     flag_export_api_url: Option<String>,
+    flag_trash_retention: Option<u32>,
+    flag_catalogs_dir: Option<String>,
+    flag_templates_dir: Option<String>,
+    flag_admin_token: Option<String>,
     flag_verbose: bool,
     flag_help: bool,
 }
 
-struct AppState {
-    database: DB,
-    bom_provider: BomProvider,
-    schematic_provider: SchematicProvider,
-}
-
-fn json_handler<F, T: Sized>(
-    request: &HttpRequest<AppState>,
-    content_retriever: F,
-) -> Result<Json<T>>
-where
-    F: FnOnce() -> Result<T, Error>,
-    T: serde::Serialize,
-{
-    info!("Request received: {}", request.path());
-
-    Ok(Json(content_retriever()?))
-}
-
-fn setup_db_routes(app: &mut CorsBuilder<AppState>) {
-    app.resource("/component-groups", |r| {
-        r.get().f(|req: &HttpRequest<AppState>| {
-            json_handler(req, || req.state().database.get_component_groups())
-        })
-    }).resource("/component-schemas", |r| {
-        r.get().f(|req: &HttpRequest<AppState>| {
-            json_handler(req, || req.state().database.get_component_schemas())
-        })
-    }).resource("/projects", |r| {
-        r.get().f(|req: &HttpRequest<AppState>| {
-            json_handler(req, || req.state().database.get_projects())
-        })
-    }).resource("/project-capabilities", |r| {
-        r.get().f(|req: &HttpRequest<AppState>| {
-            json_handler(req, || req.state().database.get_project_capabilities())
-        })
-    }).resource("/project-capability-groups", |r| {
-        r.get().f(|req: &HttpRequest<AppState>| {
-            json_handler(req, || req.state().database.get_project_capability_groups())
-        })
-    }).resource("/project-platforms", |r| {
-        r.get().f(|req: &HttpRequest<AppState>| {
-            json_handler(req, || req.state().database.get_project_platforms())
-        })
-    }).resource("/project", move |r| {
-        // FIXME: Right now we have only POST method implemented, but later on this method should be
-        // used only for new projects, if we receive project with non-empty ID we should throw and
-        // recommend using PUT method, similar behavior should be implemented for PUT method.
-        r.method(http::Method::POST).with(
-            |data: (State<AppState>, Json<projects::project::Project>)| {
-                let (state, project_to_save) = data;
-                state
-                    .database
-                    .save_project(project_to_save.into_inner())
-                    .map(|project| HttpResponse::Ok().body(project.id))
-                    .unwrap_or_else(|_| HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR))
-            },
-        )
-    }).resource("/project/{id}", move |r| {
-        r.get().f(|req: &HttpRequest<AppState>| {
-            let project_id: String = req.match_info().query("id")?;
-            json_handler(req, || req.state().database.get_project(&project_id))
-        });
-
-        r.delete().f(|req: &HttpRequest<AppState>| {
-            let project_id: String = req.match_info().query("id")?;
-            json_handler(req, || req.state().database.delete_project(&project_id))
-        })
-    });
-}
-
-fn setup_bom_routes(app: &mut CorsBuilder<AppState>) {
-    app.resource("/bom/part/{uid}", |r| {
-        r.get().f(|req: &HttpRequest<AppState>| {
-            let part_uid: String = req.match_info().query("uid")?;
-            json_handler(req, || req.state().bom_provider.get_part(part_uid))
-        })
-    }).resource("/bom/parts/{mpn}", |r| {
-        r.get().f(|req: &HttpRequest<AppState>| {
-            let mpn: String = req.match_info().query("mpn")?;
-            json_handler(req, || {
-                req.state()
-                    .bom_provider
-                    .find_parts(mpn.split(',').collect())
-            })
-        })
-    });
-}
-
-fn setup_schematic_routes(app: &mut CorsBuilder<AppState>) {
-    app.resource("/schematic/{id}", |r| {
-        r.get().f(|req: &HttpRequest<AppState>| {
-            req.match_info()
-                .query::<String>("id")
-                .map_err(|_| actix_web::error::ErrorBadRequest("Bad project id"))
-                .and_then(|project_id| {
-                    req.state()
-                        .database
-                        .get_project(&project_id)
-                        .map_err(|err| {
-                            error!(
-                                "Error occurred while retrieving project {}: {:?}",
-                                project_id, err
-                            );
-                            actix_web::error::ErrorInternalServerError(err)
-                        }).and_then(|project| {
-                            project.ok_or_else(|| {
-                                info!("Project with id {} not found", project_id);
-                                actix_web::error::ErrorNotFound(format!(
-                                    "Project with id {} not found",
-                                    project_id
-                                ))
-                            })
-                        })
-                }).and_then(|project| {
-                    req.state().schematic_provider.get(project).map_err(|err| {
-                        error!(
-                            "Error occurred while retrieving schematic for project: {:?}",
-                            err
-                        );
-                        actix_web::error::ErrorInternalServerError(err)
-                    })
-                }).and_then(|data| {
-                    Ok(HttpResponse::Ok()
-                        .content_type("image/svg+xml")
-                        .body(Bytes::from(data)))
-                })
-        })
-    });
+/// Connects to the MongoDB database, panics if connection can't be established.
+fn connect_to_database(db_name: &str, db_ip: &str, db_port: u16) -> MongoStorage {
+    let mut database = MongoStorage::new(db_name);
+    database
+        .connect(db_ip, db_port)
+        .expect("Failed to connect to the database.");
+    database
 }
 
 fn main() {
@@ -233,19 +145,121 @@ fn main() {
     let db_port = args.flag_db_port.unwrap_or(27_017);
     let db_name = args.flag_db_name.unwrap_or_else(|| "frunze".to_string());
     let bom_api_key = args.flag_bom_api_key;
+    let trash_retention_days = args.flag_trash_retention.unwrap_or(30);
+    let admin_token = args.flag_admin_token;
+
+    // Catalog management commands are executed against the database instead of starting server.
+    if args.cmd_seed || args.cmd_dump || args.cmd_migrate {
+        info!(
+            "Connecting to the database `{}` at {}:{}...",
+            db_name, db_ip, db_port
+        );
+        let database = connect_to_database(&db_name, &db_ip, db_port);
+        let directory = PathBuf::from(args.arg_dir.unwrap_or_default());
+
+        let result = if args.cmd_dump {
+            dump_catalogs(&database, &directory)
+        } else {
+            database.migrate().and_then(|applied_migrations| {
+                for migration in applied_migrations {
+                    println!(
+                        "Migration `{}` applied: {} documents upgraded",
+                        migration.name, migration.documents
+                    );
+                }
+
+                if args.cmd_seed {
+                    seed_catalogs(&database, &directory)
+                } else {
+                    Ok(vec![])
+                }
+            })
+        };
+
+        match result {
+            Ok(catalogs) => {
+                for (file_name, items_count) in catalogs {
+                    println!(
+                        "{}: {} items",
+                        directory.join(file_name).display(),
+                        items_count
+                    );
+                }
+            }
+            Err(err) => {
+                eprintln!("Command failed: {}", err);
+                process::exit(1);
+            }
+        }
+
+        return;
+    }
 
-    info!(
-        "Connecting to the database `{}` at {}:{}...",
-        db_name, db_ip, db_port
-    );
+    // In-memory storage is created only once so that all workers share the same data.
+    let storage = args.flag_storage.unwrap_or_else(|| "mongodb".to_string());
+    let memory_storage = match storage.as_ref() {
+        "mongodb" => {
+            info!(
+                "Connecting to the database `{}` at {}:{}...",
+                db_name, db_ip, db_port
+            );
+
+            // Storage relies on the unique indexes to reject duplicates, so server doesn't start
+            // until they exist even if the database has never been migrated.
+            connect_to_database(&db_name, &db_ip, db_port)
+                .create_indexes()
+                .expect("Failed to create database indexes.");
+            None
+        }
+        "memory" => {
+            info!("Using in-memory storage.");
+            Some(MemoryStorage::new())
+        }
+        _ => panic!(
+            "Unknown storage `{}`, supported values are `mongodb` and `memory`.",
+            storage
+        ),
+    };
+
+    if let Some(catalogs_dir) = args.flag_catalogs_dir {
+        let seeded_catalogs = if let Some(ref memory_storage) = memory_storage {
+            seed_catalogs(memory_storage, Path::new(&catalogs_dir))
+        } else {
+            let database = connect_to_database(&db_name, &db_ip, db_port);
+            seed_catalogs(&database, Path::new(&catalogs_dir))
+        }.expect("Failed to seed catalogs.");
+
+        for (file_name, items_count) in seeded_catalogs {
+            info!(
+                "Seeded {} items from {}.",
+                items_count,
+                Path::new(&catalogs_dir).join(file_name).display()
+            );
+        }
+    }
+
+    if let Some(templates_dir) = args.flag_templates_dir {
+        let seeded_templates = if let Some(ref memory_storage) = memory_storage {
+            seed_project_templates(memory_storage, Path::new(&templates_dir))
+        } else {
+            let database = connect_to_database(&db_name, &db_ip, db_port);
+            seed_project_templates(&database, Path::new(&templates_dir))
+        }.expect("Failed to seed project templates.");
+
+        info!(
+            "Seeded {} project templates from {}.",
+            seeded_templates, templates_dir
+        );
+    }
 
     info!("Running server at {}", host_address);
 
     server::new(move || {
-        let mut database = DB::new(db_name.clone());
-        database
-            .connect(db_ip.as_ref(), db_port)
-            .expect("Failed to connect to the database.");
+        let database: Box<dyn Storage> = if let Some(ref memory_storage) = memory_storage {
+            Box::new(memory_storage.clone())
+        } else {
+            Box::new(connect_to_database(&db_name, &db_ip, db_port))
+        };
 
         let bom_provider = BomProvider::new(bom_api_url.clone(), bom_api_key.clone());
 
@@ -264,37 +278,13 @@ fn main() {
             "generated/schematic".to_string(),
         );
 
-        let mut app = Cors::for_app(App::with_state(AppState {
+        create_app(AppState {
             database,
             bom_provider,
             schematic_provider,
-        }));
-
-        app.allowed_origin("http://localhost:4200")
-            .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
-            .allowed_headers(vec![
-                http::header::AUTHORIZATION,
-                http::header::ACCEPT,
-                http::header::CONTENT_TYPE,
-            ]);
-
-        setup_db_routes(&mut app);
-        setup_bom_routes(&mut app);
-        setup_schematic_routes(&mut app);
-
-        // Serve generated schematic files. File name is "{project-id}.fzz".
-        app.resource(r"/schematic/generated/{tail:.*}", |r| {
-            r.get()
-                .f(|req: &HttpRequest<AppState>| -> Result<NamedFile> {
-                    let mut path: PathBuf = PathBuf::from("generated/schematic");
-                    path.push(req.match_info().query::<String>("tail")?);
-
-                    info!("Serving static file: {:?}", path);
-                    Ok(NamedFile::open(path)?)
-                })
-        });
-
-        app.register()
+            trash_retention_days,
+            admin_token: admin_token.clone(),
+        })
     }).bind(host_address)
     .unwrap()
     .run();
@@ -308,12 +298,35 @@ mod tests {
             .and_then(|d| d.deserialize())
             .unwrap_or_else(|e| e.exit());
 
+        assert_eq!(args.cmd_seed, false);
+        assert_eq!(args.cmd_dump, false);
+        assert_eq!(args.cmd_migrate, false);
+        assert_eq!(args.arg_dir, None);
         assert_eq!(args.flag_verbose, false);
         assert_eq!(args.flag_ip, None);
         assert_eq!(args.flag_port, None);
         assert_eq!(args.flag_db_ip, None);
         assert_eq!(args.flag_db_port, None);
         assert_eq!(args.flag_db_name, None);
+        assert_eq!(args.flag_storage, None);
+        assert_eq!(args.flag_trash_retention, None);
+        assert_eq!(args.flag_catalogs_dir, None);
+        assert_eq!(args.flag_templates_dir, None);
+        assert_eq!(args.flag_admin_token, None);
         assert_eq!(args.flag_help, false);
     }
+
+    #[test]
+    fn args_should_support_commands() {
+        let args: super::Args = super::Docopt::new(super::USAGE)
+            .and_then(|d| {
+                d.argv(vec!["frunze_api", "seed", "catalogs", "--db-name=test"])
+                    .deserialize()
+            }).unwrap_or_else(|e| e.exit());
+
+        assert_eq!(args.cmd_seed, true);
+        assert_eq!(args.cmd_migrate, false);
+        assert_eq!(args.arg_dir, Some("catalogs".to_string()));
+        assert_eq!(args.flag_db_name, Some("test".to_string()));
+    }
 }
@@ -1,4 +1,13 @@
 pub mod project;
+pub mod project_archive;
 pub mod project_capability;
 pub mod project_capability_group;
+pub mod project_clone;
+pub mod project_compatibility;
 pub mod project_platform;
+pub mod project_query;
+pub mod project_revision;
+pub mod project_search;
+pub mod project_template;
+pub mod project_upgrade;
+pub mod trashed_project;
@@ -1,6 +1,6 @@
 use components::component::Component;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Project {
     /// Identifier of the project.
     pub id: String,
@@ -12,6 +12,116 @@ pub struct Project {
     pub capabilities: Vec<String>,
     /// Project platform (from the list of supported platforms).
     pub platform: String,
-    /// Project components.
+    /// Project components, may be omitted when only project summary is queried.
+    #[serde(default)]
     pub components: Vec<Component>,
+    /// Revision of the project that is incremented every time project is saved, it's managed by
+    /// the server and is used to detect concurrent modifications.
+    #[serde(default)]
+    pub revision: i64,
+    /// Number of milliseconds elapsed since Unix epoch when project was created, it's managed by
+    /// the server.
+    #[serde(default, rename(serialize = "createdAt", deserialize = "createdAt"))]
+    pub created_at: i64,
+    /// Number of milliseconds elapsed since Unix epoch when project was last saved, it's managed
+    /// by the server.
+    #[serde(default, rename(serialize = "updatedAt", deserialize = "updatedAt"))]
+    pub updated_at: i64,
+    /// Name of the user who created the project as reported by the client with the `X-User`
+    /// header, it isn't authenticated and is only informational.
+    #[serde(default, rename(serialize = "createdBy", deserialize = "createdBy"))]
+    pub created_by: Option<String>,
+    /// Name of the user who last saved the project as reported by the client with the `X-User`
+    /// header, it isn't authenticated and is only informational.
+    #[serde(default, rename(serialize = "updatedBy", deserialize = "updatedBy"))]
+    pub updated_by: Option<String>,
+    /// Identifier of the project this project has been cloned from, it's managed by the server.
+    #[serde(default, rename(serialize = "forkedFrom", deserialize = "forkedFrom"))]
+    pub forked_from: Option<String>,
+}
+
+impl Project {
+    /// Resets timestamps and authorship of the project that is about to be created, values sent by
+    /// the client in the project body are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `author` - Name of the user who creates the project, if known.
+    /// * `timestamp` - Number of milliseconds elapsed since Unix epoch.
+    pub fn track_creation(&mut self, author: Option<String>, timestamp: i64) {
+        self.created_at = timestamp;
+        self.created_by = author.clone();
+        self.updated_at = timestamp;
+        self.updated_by = author;
+    }
+
+    /// Sets timestamps and authorship of the project that is about to replace `current_project`,
+    /// creation metadata and project origin are preserved.
+    ///
+    /// # Arguments
+    ///
+    /// * `current_project` - Currently stored version of the project.
+    /// * `author` - Name of the user who updates the project, if known.
+    /// * `timestamp` - Number of milliseconds elapsed since Unix epoch.
+    pub fn track_update(
+        &mut self,
+        current_project: &Project,
+        author: Option<String>,
+        timestamp: i64,
+    ) {
+        self.created_at = current_project.created_at;
+        self.created_by = current_project.created_by.clone();
+        self.updated_at = timestamp;
+        self.updated_by = author;
+        self.forked_from = current_project.forked_from.clone();
+    }
+
+    /// Returns project summary that doesn't include project components.
+    pub fn summary(&self) -> ProjectSummary {
+        ProjectSummary {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            capabilities: self.capabilities.clone(),
+            platform: self.platform.clone(),
+            revision: self.revision,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            created_by: self.created_by.clone(),
+            updated_by: self.updated_by.clone(),
+            forked_from: self.forked_from.clone(),
+        }
+    }
+}
+
+/// Describes project without its components, used to list projects.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectSummary {
+    /// Identifier of the project.
+    pub id: String,
+    /// Name of the project.
+    pub name: String,
+    /// Description of the project.
+    pub description: String,
+    /// List of the project capabilities.
+    pub capabilities: Vec<String>,
+    /// Project platform.
+    pub platform: String,
+    /// Current revision of the project.
+    pub revision: i64,
+    /// Number of milliseconds elapsed since Unix epoch when project was created.
+    #[serde(rename(serialize = "createdAt", deserialize = "createdAt"))]
+    pub created_at: i64,
+    /// Number of milliseconds elapsed since Unix epoch when project was last saved.
+    #[serde(rename(serialize = "updatedAt", deserialize = "updatedAt"))]
+    pub updated_at: i64,
+    /// Name of the user who created the project.
+    #[serde(rename(serialize = "createdBy", deserialize = "createdBy"))]
+    pub created_by: Option<String>,
+    /// Name of the user who last saved the project.
+    #[serde(rename(serialize = "updatedBy", deserialize = "updatedBy"))]
+    pub updated_by: Option<String>,
+    /// Identifier of the project this project has been cloned from.
+    #[serde(rename(serialize = "forkedFrom", deserialize = "forkedFrom"))]
+    pub forked_from: Option<String>,
 }
@@ -0,0 +1,372 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use failure::{err_msg, Error, Fail};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{self, Value};
+use zip::result::ZipError;
+use zip::write::FileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use components::component_schema::ComponentSchema;
+use projects::project::{Project, ProjectSummary};
+
+/// Version of the archive format, it's incremented every time archive layout changes in a
+/// backward incompatible way.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// Maximum size of the uncompressed archive file in bytes, archive is rejected if any of its files
+/// is larger, so that small archive can't be unpacked into arbitrary amount of memory.
+pub const MAX_ARCHIVE_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Maximum size of the archive itself in bytes, it fits all archive files of the maximum size
+/// even if they are stored without compression.
+pub const MAX_ARCHIVE_SIZE: u64 = 5 * MAX_ARCHIVE_FILE_SIZE;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const PROJECT_FILE: &str = "project.json";
+const SCHEMAS_FILE: &str = "schemas.json";
+const SCHEMATIC_FILE: &str = "schematic.svg";
+const BOM_FILE: &str = "bom.json";
+
+/// Describes content of the project archive.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectArchiveManifest {
+    /// Version of the archive format.
+    pub version: u32,
+    /// Number of milliseconds elapsed since Unix epoch when archive was created.
+    #[serde(rename(serialize = "exportedAt", deserialize = "exportedAt"))]
+    pub exported_at: i64,
+    /// Identifier of the exported project.
+    #[serde(rename(serialize = "projectId", deserialize = "projectId"))]
+    pub project_id: String,
+}
+
+/// Describes outcome of the project import.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectImportReport {
+    /// Summary of the imported project.
+    pub project: ProjectSummary,
+    /// Identifier the project had in the archive.
+    #[serde(rename(serialize = "originalId", deserialize = "originalId"))]
+    pub original_id: String,
+    /// Indicates whether project got a new identifier since the original one was already taken.
+    #[serde(rename(serialize = "idRemapped", deserialize = "idRemapped"))]
+    pub id_remapped: bool,
+}
+
+/// Describes archive file that is larger than `MAX_ARCHIVE_FILE_SIZE` once uncompressed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveFileTooLarge {
+    /// Name of the file within the archive.
+    pub name: String,
+}
+
+impl fmt::Display for ArchiveFileTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Archive file {} is larger than {} bytes",
+            self.name, MAX_ARCHIVE_FILE_SIZE
+        )
+    }
+}
+
+impl Fail for ArchiveFileTooLarge {}
+
+/// Portable representation of the project that can be moved between server instances.
+#[derive(Debug, Clone)]
+pub struct ProjectArchive {
+    /// Archive manifest.
+    pub manifest: ProjectArchiveManifest,
+    /// Exported project.
+    pub project: Project,
+    /// Schemas of all components used by the project, in the versions components are pinned to.
+    pub schemas: Vec<ComponentSchema>,
+    /// Generated schematic image (SVG) of the project, if requested.
+    pub schematic: Option<Vec<u8>>,
+    /// Bill of materials (MPN <-> part map) of the project, if requested.
+    pub bom: Option<Value>,
+}
+
+impl ProjectArchive {
+    /// Creates archive for the project, only schemas of the components used by the project are
+    /// included: the versions components are pinned to or the latest versions for the components
+    /// that aren't pinned (or pinned to the unknown versions).
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project to archive.
+    /// * `schemas` - List of all known component schemas, both current versions and the previous
+    /// versions project components are pinned to.
+    /// * `timestamp` - Number of milliseconds elapsed since Unix epoch.
+    pub fn new(project: Project, schemas: &[ComponentSchema], timestamp: i64) -> Self {
+        let mut used_schemas: Vec<ComponentSchema> = vec![];
+        for component in &project.components {
+            let schemas_of_type = schemas
+                .iter()
+                .filter(|schema| schema.type_name == component.type_name);
+            let schema = schemas_of_type
+                .clone()
+                .find(|schema| Some(schema.version) == component.schema_version)
+                .or_else(|| schemas_of_type.max_by_key(|schema| schema.version));
+
+            if let Some(schema) = schema {
+                let is_included = used_schemas.iter().any(|used_schema| {
+                    used_schema.type_name == schema.type_name
+                        && used_schema.version == schema.version
+                });
+                if !is_included {
+                    used_schemas.push(schema.clone());
+                }
+            }
+        }
+
+        ProjectArchive {
+            manifest: ProjectArchiveManifest {
+                version: ARCHIVE_VERSION,
+                exported_at: timestamp,
+                project_id: project.id.clone(),
+            },
+            schemas: used_schemas,
+            project,
+            schematic: None,
+            bom: None,
+        }
+    }
+
+    /// Serializes archive into the zip file.
+    pub fn write(&self) -> Result<Vec<u8>, Error> {
+        let mut zip_writer = ZipWriter::new(io::Cursor::new(Vec::new()));
+
+        write_json(&mut zip_writer, MANIFEST_FILE, &self.manifest)?;
+        write_json(&mut zip_writer, PROJECT_FILE, &self.project)?;
+        write_json(&mut zip_writer, SCHEMAS_FILE, &self.schemas)?;
+
+        if let Some(ref schematic) = self.schematic {
+            zip_writer.start_file(SCHEMATIC_FILE, FileOptions::default())?;
+            zip_writer.write_all(schematic)?;
+        }
+
+        if let Some(ref bom) = self.bom {
+            write_json(&mut zip_writer, BOM_FILE, bom)?;
+        }
+
+        Ok(zip_writer.finish()?.into_inner())
+    }
+
+    /// Deserializes archive from the zip file, fails if archive is malformed or has unsupported
+    /// version, and with `ArchiveFileTooLarge` if any of the archive files is too large.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Content of the zip file.
+    pub fn read(data: &[u8]) -> Result<Self, Error> {
+        let mut zip_archive = ZipArchive::new(io::Cursor::new(data))
+            .map_err(|err| err_msg(format!("Archive is not a valid zip file: {}", err)))?;
+
+        let manifest: ProjectArchiveManifest = read_json(&mut zip_archive, MANIFEST_FILE)?
+            .ok_or_else(|| err_msg(format!("Archive doesn't contain {}", MANIFEST_FILE)))?;
+        if manifest.version == 0 || manifest.version > ARCHIVE_VERSION {
+            return Err(err_msg(format!(
+                "Archive version {} is not supported, the latest supported version is {}",
+                manifest.version, ARCHIVE_VERSION
+            )));
+        }
+
+        let project = read_json(&mut zip_archive, PROJECT_FILE)?
+            .ok_or_else(|| err_msg(format!("Archive doesn't contain {}", PROJECT_FILE)))?;
+        let schemas = read_json(&mut zip_archive, SCHEMAS_FILE)?
+            .ok_or_else(|| err_msg(format!("Archive doesn't contain {}", SCHEMAS_FILE)))?;
+        let schematic = read_file(&mut zip_archive, SCHEMATIC_FILE)?;
+        let bom = read_json(&mut zip_archive, BOM_FILE)?;
+
+        Ok(ProjectArchive {
+            manifest,
+            project,
+            schemas,
+            schematic,
+            bom,
+        })
+    }
+
+    /// Returns sorted list of the component schemas used by the archived project that aren't among
+    /// the `schemas`. Components pinned to the specific schema version require exactly that
+    /// version and are reported as `type@version`, the rest are reported as `type`.
+    ///
+    /// # Arguments
+    ///
+    /// * `schemas` - List of the component schemas known to the server instance, including the
+    /// previous versions components are pinned to.
+    pub fn missing_schemas(&self, schemas: &[ComponentSchema]) -> Vec<String> {
+        let mut missing_schemas: Vec<String> = used_component_schemas(&self.project)
+            .into_iter()
+            .filter(|&(type_name, version)| {
+                !schemas.iter().any(|schema| {
+                    schema.type_name == type_name
+                        && version.map_or(true, |version| schema.version == version)
+                })
+            }).map(|(type_name, version)| match version {
+                Some(version) => format!("{}@{}", type_name, version),
+                None => type_name.to_string(),
+            }).collect();
+        missing_schemas.sort();
+
+        missing_schemas
+    }
+}
+
+fn used_component_schemas(project: &Project) -> HashSet<(&str, Option<i64>)> {
+    project
+        .components
+        .iter()
+        .map(|component| (component.type_name.as_str(), component.schema_version))
+        .collect()
+}
+
+fn write_json<W, T>(zip_writer: &mut ZipWriter<W>, name: &str, value: &T) -> Result<(), Error>
+where
+    W: Write + io::Seek,
+    T: Serialize,
+{
+    zip_writer.start_file(name, FileOptions::default())?;
+    serde_json::to_writer_pretty(zip_writer, value)?;
+
+    Ok(())
+}
+
+/// Reads content of the file from the archive, returns `None` if there is no such file. Fails if
+/// file is larger than `MAX_ARCHIVE_FILE_SIZE`.
+fn read_file<R>(zip_archive: &mut ZipArchive<R>, name: &str) -> Result<Option<Vec<u8>>, Error>
+where
+    R: Read + io::Seek,
+{
+    let file = match zip_archive.by_name(name) {
+        Ok(file) => file,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let too_large = || ArchiveFileTooLarge {
+        name: name.to_string(),
+    };
+    if file.size() > MAX_ARCHIVE_FILE_SIZE {
+        return Err(too_large().into());
+    }
+
+    // Size recorded in the archive can't be trusted, so reading stops once the limit is exceeded.
+    let mut buffer = Vec::new();
+    file.take(MAX_ARCHIVE_FILE_SIZE + 1)
+        .read_to_end(&mut buffer)?;
+    if buffer.len() as u64 > MAX_ARCHIVE_FILE_SIZE {
+        return Err(too_large().into());
+    }
+
+    Ok(Some(buffer))
+}
+
+/// Reads and deserializes JSON file from the archive, returns `None` if there is no such file.
+fn read_json<R, T>(zip_archive: &mut ZipArchive<R>, name: &str) -> Result<Option<T>, Error>
+where
+    R: Read + io::Seek,
+    T: DeserializeOwned,
+{
+    match read_file(zip_archive, name)? {
+        Some(data) => serde_json::from_slice(&data)
+            .map(Some)
+            .map_err(|err| err_msg(format!("Archive file {} is not valid: {}", name, err))),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArchiveFileTooLarge, ProjectArchive, ARCHIVE_VERSION, MAX_ARCHIVE_FILE_SIZE};
+    use projects::project::Project;
+    use test_fixtures::{self, component, schema};
+
+    fn project() -> Project {
+        test_fixtures::project(
+            "project-1",
+            "project",
+            vec![
+                component("led-1", "led", &[]),
+                component("button-1", "button", &[]),
+            ],
+        )
+    }
+
+    #[test]
+    fn archive_should_be_written_and_read() {
+        let mut archive = ProjectArchive::new(
+            project(),
+            &[schema("led"), schema("button"), schema("servo")],
+            10,
+        );
+        archive.schematic = Some(b"<svg></svg>".to_vec());
+
+        let archive = ProjectArchive::read(&archive.write().unwrap()).unwrap();
+        assert_eq!(archive.manifest.version, ARCHIVE_VERSION);
+        assert_eq!(archive.manifest.exported_at, 10);
+        assert_eq!(archive.project.id, "project-1");
+        assert_eq!(archive.project.components.len(), 2);
+        assert_eq!(archive.schemas.len(), 2);
+        assert_eq!(archive.schematic, Some(b"<svg></svg>".to_vec()));
+        assert!(archive.bom.is_none());
+
+        assert_eq!(
+            archive.missing_schemas(&[schema("led")]),
+            vec!["button".to_string()]
+        );
+
+        assert!(ProjectArchive::read(b"not a zip").is_err());
+    }
+
+    #[test]
+    fn archive_should_include_schema_versions_components_use() {
+        let mut project = project();
+        project.components[0].schema_version = Some(1);
+
+        let mut led_v2 = schema("led");
+        led_v2.version = 2;
+        let mut button_v3 = schema("button");
+        button_v3.version = 3;
+
+        // Pinned components use the pinned versions, the rest use the latest ones.
+        let archive = ProjectArchive::new(
+            project,
+            &[led_v2, schema("button"), button_v3, schema("led")],
+            10,
+        );
+        let schema_versions: Vec<(&str, i64)> = archive
+            .schemas
+            .iter()
+            .map(|schema| (schema.type_name.as_str(), schema.version))
+            .collect();
+        assert_eq!(schema_versions, vec![("led", 1), ("button", 3)]);
+
+        // Pinned version has to be known, the latest one isn't enough.
+        let mut led_v2 = schema("led");
+        led_v2.version = 2;
+        assert_eq!(
+            archive.missing_schemas(&[led_v2, schema("button")]),
+            vec!["led@1".to_string()]
+        );
+    }
+
+    #[test]
+    fn archive_with_too_large_file_should_be_rejected() {
+        let mut archive = ProjectArchive::new(project(), &[], 10);
+        archive.schematic = Some(vec![b' '; MAX_ARCHIVE_FILE_SIZE as usize + 1]);
+
+        let err = ProjectArchive::read(&archive.write().unwrap()).unwrap_err();
+        assert_eq!(
+            err.downcast::<ArchiveFileTooLarge>().unwrap(),
+            ArchiveFileTooLarge {
+                name: "schematic.svg".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use components::component::Component;
+use components::component_property_schema::ComponentPropertyValueKind;
+use components::component_schema::ComponentSchema;
+use projects::project::Project;
+
+/// Creates a copy of the project that can be saved as a new project: identifier, timestamps and
+/// authorship are reset, every component gets a new identifier and all references to the
+/// components (trigger actions and `Component`-kind properties) are updated accordingly.
+///
+/// # Arguments
+///
+/// * `project` - Project to clone.
+/// * `schemas` - List of the component schemas used to find `Component`-kind properties.
+/// * `name` - Name of the cloned project.
+pub fn clone_project(project: &Project, schemas: &[ComponentSchema], name: String) -> Project {
+    let mut cloned_project = project.clone();
+    cloned_project.id = String::new();
+    cloned_project.name = name;
+    cloned_project.revision = 0;
+    cloned_project.track_creation(None, 0);
+    cloned_project.forked_from = Some(project.id.clone());
+
+    regenerate_component_ids(&mut cloned_project.components, schemas);
+
+    cloned_project
+}
+
+/// Assigns new unique identifier to every component and updates all references to the
+/// components (trigger actions and `Component`-kind properties) accordingly.
+///
+/// # Arguments
+///
+/// * `components` - List of the components to update.
+/// * `schemas` - List of the component schemas used to find `Component`-kind properties.
+pub fn regenerate_component_ids(components: &mut [Component], schemas: &[ComponentSchema]) {
+    let component_ids: HashMap<String, String> = components
+        .iter()
+        .map(|component| (component.id.clone(), Uuid::new_v4().to_string()))
+        .collect();
+
+    for component in components {
+        if let Some(component_id) = component_ids.get(&component.id) {
+            component.id = component_id.clone();
+        }
+
+        if let Some(schema) = schemas
+            .iter()
+            .find(|schema| schema.type_name == component.type_name)
+        {
+            for (property_name, property_value) in &mut component.properties {
+                let is_component_reference = match schema.properties.get(property_name) {
+                    Some(property_schema) => match property_schema.kind {
+                        ComponentPropertyValueKind::Component(_) => true,
+                        _ => false,
+                    },
+                    None => false,
+                };
+
+                if is_component_reference {
+                    if let Some(component_id) = component_ids.get(property_value.as_str()) {
+                        *property_value = component_id.clone();
+                    }
+                }
+            }
+        }
+
+        for trigger_actions in component.triggers.values_mut() {
+            for trigger_action in trigger_actions {
+                if let Some(component_id) = component_ids.get(&trigger_action.component) {
+                    trigger_action.component = component_id.clone();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::clone_project;
+    use components::component::ComponentTriggerAction;
+    use components::component_property_schema::{
+        ComponentPropertySchema, ComponentPropertyValueKind,
+    };
+    use components::component_schema::ComponentSchema;
+    use test_fixtures::{self, component, project};
+
+    fn schema(
+        type_name: &str,
+        properties: Vec<(&str, ComponentPropertyValueKind)>,
+    ) -> ComponentSchema {
+        let mut schema = test_fixtures::schema(type_name);
+        schema.properties = properties
+            .into_iter()
+            .map(|(property_type, kind)| {
+                (
+                    property_type.to_string(),
+                    ComponentPropertySchema {
+                        type_name: property_type.to_string(),
+                        name: property_type.to_string(),
+                        description: String::new(),
+                        default_value: String::new(),
+                        kind,
+                    },
+                )
+            }).collect();
+        schema
+    }
+
+    #[test]
+    fn clone_should_rewrite_component_references() {
+        let mut button = component(
+            "button-1",
+            "button",
+            &[("led", "led-1"), ("label", "led-1")],
+        );
+        button.triggers.insert(
+            "pressed".to_string(),
+            vec![ComponentTriggerAction {
+                action: "toggle".to_string(),
+                component: "led-1".to_string(),
+            }],
+        );
+
+        let mut project = project(
+            "project-1",
+            "project",
+            vec![component("led-1", "led", &[]), button],
+        );
+        project.revision = 5;
+        project.created_at = 1;
+        project.updated_at = 2;
+        project.created_by = Some("alice".to_string());
+        project.updated_by = Some("bob".to_string());
+
+        let schemas = vec![
+            schema("led", vec![]),
+            schema(
+                "button",
+                vec![
+                    ("led", ComponentPropertyValueKind::Component(vec![])),
+                    ("label", ComponentPropertyValueKind::Custom),
+                ],
+            ),
+        ];
+
+        let cloned_project = clone_project(&project, &schemas, "clone".to_string());
+        assert!(cloned_project.id.is_empty());
+        assert_eq!(cloned_project.name, "clone");
+        assert_eq!(cloned_project.revision, 0);
+        assert_eq!(cloned_project.created_by, None);
+        assert_eq!(cloned_project.forked_from, Some("project-1".to_string()));
+
+        let led_id = &cloned_project.components[0].id;
+        let button = &cloned_project.components[1];
+        assert_ne!(led_id, "led-1");
+        assert_ne!(button.id, "button-1");
+        assert_eq!(&button.properties["led"], led_id);
+        // Only `Component`-kind properties are treated as references.
+        assert_eq!(button.properties["label"], "led-1");
+        assert_eq!(&button.triggers["pressed"][0].component, led_id);
+
+        // Source project is left intact.
+        assert_eq!(project.components[1].properties["led"], "led-1");
+    }
+}
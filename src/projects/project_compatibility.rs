@@ -0,0 +1,51 @@
+use projects::project::Project;
+use projects::project_platform::ProjectPlatform;
+
+/// Describes whether platform supports all the capabilities required by the project.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlatformCompatibility {
+    /// Type of the platform.
+    pub platform: String,
+    /// Human-readable short platform name.
+    pub name: String,
+    /// Indicates whether platform supports all project capabilities.
+    pub compatible: bool,
+    /// Project capabilities that aren't supported by the platform.
+    #[serde(rename(serialize = "missingCapabilities", deserialize = "missingCapabilities"))]
+    pub missing_capabilities: Vec<String>,
+}
+
+/// Checks which platforms, other than the one project currently uses, support all project
+/// capabilities. Compatible platforms go first.
+///
+/// # Arguments
+///
+/// * `project` - Project to check compatibility for.
+/// * `platforms` - List of all known project platforms.
+pub fn check_compatibility(
+    project: &Project,
+    platforms: &[ProjectPlatform],
+) -> Vec<PlatformCompatibility> {
+    let mut compatibility: Vec<PlatformCompatibility> = platforms
+        .iter()
+        .filter(|platform| platform.type_name != project.platform)
+        .map(|platform| {
+            let missing_capabilities: Vec<String> = project
+                .capabilities
+                .iter()
+                .filter(|capability| !platform.capabilities.contains(capability))
+                .cloned()
+                .collect();
+
+            PlatformCompatibility {
+                platform: platform.type_name.clone(),
+                name: platform.name.clone(),
+                compatible: missing_capabilities.is_empty(),
+                missing_capabilities,
+            }
+        }).collect();
+
+    compatibility.sort_by_key(|platform| !platform.compatible);
+
+    compatibility
+}
@@ -1,4 +1,4 @@
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ProjectPlatform {
     #[serde(rename(serialize = "type", deserialize = "type"))]
     pub type_name: String,
@@ -0,0 +1,40 @@
+/// Field projects can be sorted by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProjectSortField {
+    Name,
+    CreatedAt,
+    UpdatedAt,
+}
+
+/// Describes which projects should be queried from the storage and in what order.
+#[derive(Debug, Clone)]
+pub struct ProjectQuery {
+    /// If specified, only projects for this platform are returned.
+    pub platform: Option<String>,
+    /// Only projects that have all of these capabilities are returned.
+    pub capabilities: Vec<String>,
+    /// Field projects are sorted by.
+    pub sort_field: ProjectSortField,
+    /// Indicates whether projects are sorted in descending order.
+    pub sort_descending: bool,
+    /// Number of the matching projects to skip.
+    pub offset: usize,
+    /// Maximum number of the projects to return.
+    pub limit: usize,
+    /// Indicates whether project components are needed, storage may omit them otherwise.
+    pub include_components: bool,
+}
+
+impl Default for ProjectQuery {
+    fn default() -> Self {
+        ProjectQuery {
+            platform: None,
+            capabilities: vec![],
+            sort_field: ProjectSortField::Name,
+            sort_descending: false,
+            offset: 0,
+            limit: 50,
+            include_components: true,
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use projects::project::Project;
+
+/// Describes immutable snapshot of the project recorded every time project is saved.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectRevision {
+    /// Identifier of the project this revision belongs to.
+    #[serde(rename(serialize = "projectId", deserialize = "projectId"))]
+    pub project_id: String,
+    /// Revision number of the project snapshot.
+    pub revision: i64,
+    /// Number of milliseconds elapsed since Unix epoch when revision was recorded.
+    pub timestamp: i64,
+    /// Optional message describing the change.
+    pub message: Option<String>,
+    /// Name of the user who made the change, if known.
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Snapshot of the project at this revision.
+    pub project: Project,
+}
+
+impl ProjectRevision {
+    /// Creates revision for the current state of the project, revision is recorded at the time
+    /// and by the user project was last updated by.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project to record revision for.
+    /// * `message` - Optional message describing the change.
+    pub fn new(project: &Project, message: Option<String>) -> Self {
+        ProjectRevision {
+            project_id: project.id.clone(),
+            revision: project.revision,
+            timestamp: project.updated_at,
+            message,
+            author: project.updated_by.clone(),
+            project: project.clone(),
+        }
+    }
+
+    /// Returns revision summary that doesn't include project snapshot.
+    pub fn summary(&self) -> ProjectRevisionSummary {
+        ProjectRevisionSummary {
+            revision: self.revision,
+            timestamp: self.timestamp,
+            message: self.message.clone(),
+            author: self.author.clone(),
+        }
+    }
+}
+
+/// Describes project revision without project snapshot, used to list revisions.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectRevisionSummary {
+    /// Revision number of the project snapshot.
+    pub revision: i64,
+    /// Number of milliseconds elapsed since Unix epoch when revision was recorded.
+    pub timestamp: i64,
+    /// Optional message describing the change.
+    pub message: Option<String>,
+    /// Name of the user who made the change, if known.
+    pub author: Option<String>,
+}
+
+/// Describes who makes the change to the project and why.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectChange {
+    /// Name of the user who makes the change, if known.
+    pub author: Option<String>,
+    /// Optional message describing the change.
+    pub message: Option<String>,
+}
@@ -0,0 +1,351 @@
+use json_patch::pointer::escape_token;
+use projects::project::{Project, ProjectSummary};
+
+/// Maximum number of characters in the highlighted snippet, not counting highlight markers.
+const SNIPPET_LENGTH: usize = 80;
+
+/// Number of characters preceding the first match that are included into the snippet.
+const SNIPPET_LEADING_CONTEXT: usize = 20;
+
+const NAME_WEIGHT: u32 = 8;
+const DESCRIPTION_WEIGHT: u32 = 4;
+const COMPONENT_TYPE_WEIGHT: u32 = 2;
+const COMPONENT_PROPERTY_WEIGHT: u32 = 1;
+
+/// Describes matched project field with the highlighted snippet of its value.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SearchHighlight {
+    /// JSON Pointer to the matched project field, e.g. `/name` or `/components/0/type`.
+    pub field: String,
+    /// Part of the field value where all matched terms are wrapped into `<em>` tags, the rest of
+    /// the value is HTML-escaped.
+    pub snippet: String,
+}
+
+/// Describes project that matches search query.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectSearchResult {
+    /// Summary of the matched project.
+    pub project: ProjectSummary,
+    /// Relevance score of the project, the higher the better.
+    pub score: u32,
+    /// List of the matched project fields.
+    pub highlights: Vec<SearchHighlight>,
+}
+
+/// Searches for the projects that contain all terms from the query in their name, description,
+/// component types or component property values. Matches are case-insensitive, matches in the
+/// name weigh more than matches in the description, and those weigh more than matches in the
+/// components. Whole word matches weigh twice as much as partial ones. The most relevant
+/// projects go first.
+///
+/// # Arguments
+///
+/// * `projects` - List of the projects to search through.
+/// * `query` - Search query, any non-alphanumeric characters separate query terms.
+pub fn search(projects: &[Project], query: &str) -> Vec<ProjectSearchResult> {
+    let terms = tokenize(query);
+    if terms.is_empty() {
+        return vec![];
+    }
+
+    let mut results: Vec<ProjectSearchResult> = projects
+        .iter()
+        .filter_map(|project| search_project(project, &terms))
+        .collect();
+
+    results.sort_by(|result_a, result_b| {
+        result_b
+            .score
+            .cmp(&result_a.score)
+            .then_with(|| result_a.project.name.cmp(&result_b.project.name))
+            .then_with(|| result_a.project.id.cmp(&result_b.project.id))
+    });
+
+    results
+}
+
+/// Splits query into unique lowercase terms, terms consist of alphanumeric characters only.
+pub fn query_terms(query: &str) -> Vec<String> {
+    tokenize(query)
+        .into_iter()
+        .map(|term| term.into_iter().collect())
+        .collect()
+}
+
+/// Splits query into unique lowercase terms.
+fn tokenize(query: &str) -> Vec<Vec<char>> {
+    let mut terms: Vec<Vec<char>> = vec![];
+    for token in query.split(|c: char| !c.is_alphanumeric()) {
+        let term = lowercase(token);
+        if !term.is_empty() && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+
+    terms
+}
+
+/// Lowercases every character of the text preserving one-to-one mapping between characters.
+fn lowercase(text: &str) -> Vec<char> {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+fn search_project(project: &Project, terms: &[Vec<char>]) -> Option<ProjectSearchResult> {
+    let mut fields: Vec<(String, &str, u32)> = vec![
+        ("/name".to_string(), &project.name, NAME_WEIGHT),
+        (
+            "/description".to_string(),
+            &project.description,
+            DESCRIPTION_WEIGHT,
+        ),
+    ];
+
+    for (index, component) in project.components.iter().enumerate() {
+        fields.push((
+            format!("/components/{}/type", index),
+            &component.type_name,
+            COMPONENT_TYPE_WEIGHT,
+        ));
+
+        let mut properties: Vec<(&String, &String)> = component.properties.iter().collect();
+        properties.sort();
+        for (name, value) in properties {
+            fields.push((
+                format!("/components/{}/properties/{}", index, escape_token(name)),
+                value,
+                COMPONENT_PROPERTY_WEIGHT,
+            ));
+        }
+    }
+
+    let mut matched_terms = vec![false; terms.len()];
+    let mut score = 0;
+    let mut highlights = vec![];
+    for (field, value, weight) in fields {
+        let text: Vec<char> = value.chars().collect();
+        let lowercase_text = lowercase(value);
+
+        let mut ranges = vec![];
+        for (term_index, term) in terms.iter().enumerate() {
+            let term_ranges = find_term(&lowercase_text, term);
+            if term_ranges.is_empty() {
+                continue;
+            }
+
+            matched_terms[term_index] = true;
+            score += if term_ranges
+                .iter()
+                .any(|&(start, end)| is_whole_word(&text, start, end))
+            {
+                weight * 2
+            } else {
+                weight
+            };
+            ranges.extend(term_ranges);
+        }
+
+        if !ranges.is_empty() {
+            highlights.push(SearchHighlight {
+                field,
+                snippet: highlight(&text, ranges),
+            });
+        }
+    }
+
+    if matched_terms.iter().all(|matched| *matched) {
+        Some(ProjectSearchResult {
+            project: project.summary(),
+            score,
+            highlights,
+        })
+    } else {
+        None
+    }
+}
+
+/// Finds all non-overlapping occurrences of the term in the text, returns character ranges.
+fn find_term(text: &[char], term: &[char]) -> Vec<(usize, usize)> {
+    let mut ranges = vec![];
+    let mut start = 0;
+    while start + term.len() <= text.len() {
+        if &text[start..start + term.len()] == term {
+            ranges.push((start, start + term.len()));
+            start += term.len();
+        } else {
+            start += 1;
+        }
+    }
+
+    ranges
+}
+
+fn is_whole_word(text: &[char], start: usize, end: usize) -> bool {
+    (start == 0 || !text[start - 1].is_alphanumeric())
+        && (end == text.len() || !text[end].is_alphanumeric())
+}
+
+/// Builds snippet of the text around the first match with all matches wrapped into `<em>` tags.
+fn highlight(text: &[char], mut ranges: Vec<(usize, usize)>) -> String {
+    ranges.sort();
+
+    let mut merged_ranges: Vec<(usize, usize)> = vec![];
+    for (start, end) in ranges {
+        match merged_ranges.last_mut() {
+            Some(last_range) if start <= last_range.1 => {
+                last_range.1 = last_range.1.max(end);
+                continue;
+            }
+            _ => {}
+        }
+        merged_ranges.push((start, end));
+    }
+
+    let (snippet_start, snippet_end) = if text.len() <= SNIPPET_LENGTH {
+        (0, text.len())
+    } else {
+        let start = merged_ranges[0]
+            .0
+            .saturating_sub(SNIPPET_LEADING_CONTEXT)
+            .min(text.len() - SNIPPET_LENGTH);
+        (start, start + SNIPPET_LENGTH)
+    };
+
+    let mut snippet = String::new();
+    if snippet_start > 0 {
+        snippet.push('…');
+    }
+
+    let mut position = snippet_start;
+    for (start, end) in merged_ranges {
+        let start = start.max(snippet_start);
+        let end = end.min(snippet_end);
+        if start >= end {
+            continue;
+        }
+
+        push_escaped(&mut snippet, &text[position..start]);
+        snippet.push_str("<em>");
+        push_escaped(&mut snippet, &text[start..end]);
+        snippet.push_str("</em>");
+        position = end;
+    }
+    push_escaped(&mut snippet, &text[position..snippet_end]);
+
+    if snippet_end < text.len() {
+        snippet.push('…');
+    }
+
+    snippet
+}
+
+fn push_escaped(snippet: &mut String, text: &[char]) {
+    for c in text {
+        match c {
+            '<' => snippet.push_str("&lt;"),
+            '>' => snippet.push_str("&gt;"),
+            '&' => snippet.push_str("&amp;"),
+            '"' => snippet.push_str("&quot;"),
+            _ => snippet.push(*c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{query_terms, search, SearchHighlight};
+    use components::component::Component;
+    use projects::project::Project;
+    use test_fixtures::{self, component};
+
+    fn project(id: &str, name: &str, description: &str, components: Vec<Component>) -> Project {
+        let mut project = test_fixtures::project(id, name, components);
+        project.description = description.to_string();
+        project
+    }
+
+    #[test]
+    fn search_should_rank_and_highlight_matches() {
+        let projects = vec![
+            project(
+                "1",
+                "Weather station",
+                "Shows temperature on the <LED> display",
+                vec![component("led-1", "led", &[("color", "Red")])],
+            ),
+            project("2", "Led strip", "", vec![]),
+            project("3", "Door bell", "Rings when button is pressed", vec![]),
+        ];
+
+        let results = search(&projects, "LED");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].project.id, "2");
+        assert_eq!(
+            results[0].highlights,
+            vec![SearchHighlight {
+                field: "/name".to_string(),
+                snippet: "<em>Led</em> strip".to_string(),
+            }]
+        );
+
+        assert_eq!(results[1].project.id, "1");
+        assert_eq!(
+            results[1].highlights,
+            vec![
+                SearchHighlight {
+                    field: "/description".to_string(),
+                    snippet: "Shows temperature on the &lt;<em>LED</em>&gt; display".to_string(),
+                },
+                SearchHighlight {
+                    field: "/components/0/type".to_string(),
+                    snippet: "<em>led</em>".to_string(),
+                },
+            ]
+        );
+
+        // All terms should match.
+        let results = search(&projects, "led red");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].project.id, "1");
+
+        assert!(search(&projects, "  ").is_empty());
+    }
+
+    #[test]
+    fn search_should_trim_long_snippets() {
+        let description = format!("{} button {}", "a".repeat(100), "b".repeat(100));
+        let projects = vec![project("1", "Project", &description, vec![])];
+
+        let results = search(&projects, "button");
+        assert_eq!(
+            results[0].highlights[0].snippet,
+            format!("…{} <em>button</em> {}…", "a".repeat(19), "b".repeat(53))
+        );
+    }
+
+    #[test]
+    fn highlighted_property_names_should_be_escaped() {
+        let projects = vec![project(
+            "1",
+            "Project",
+            "",
+            vec![component("led-1", "led", &[("pin/mode~1", "Output")])],
+        )];
+
+        let results = search(&projects, "output");
+        assert_eq!(
+            results[0].highlights[0].field,
+            "/components/0/properties/pin~1mode~01"
+        );
+    }
+
+    #[test]
+    fn query_should_be_split_into_unique_terms() {
+        assert_eq!(
+            query_terms("Red, LED/red.led-strip"),
+            vec!["red", "led", "strip"]
+        );
+    }
+}
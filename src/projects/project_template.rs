@@ -0,0 +1,69 @@
+use components::component::Component;
+use projects::project::Project;
+
+/// Describes project template that new projects can be created from.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectTemplate {
+    /// Identifier of the template.
+    pub id: String,
+    /// Human-readable short template name.
+    pub name: String,
+    /// Human-readable template long description.
+    pub description: String,
+    /// List of the capabilities projects created from the template have.
+    pub capabilities: Vec<String>,
+    /// Platform of the projects created from the template.
+    pub platform: String,
+    /// Components of the projects created from the template.
+    #[serde(default)]
+    pub components: Vec<Component>,
+    /// Identifier of the project template has been created from, if any.
+    #[serde(
+        default,
+        rename(serialize = "sourceProject", deserialize = "sourceProject")
+    )]
+    pub source_project: Option<String>,
+}
+
+impl ProjectTemplate {
+    /// Creates template from the existing project.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Identifier of the template.
+    /// * `project` - Project to create template from.
+    pub fn from_project<T: Into<String>>(id: T, project: &Project) -> Self {
+        ProjectTemplate {
+            id: id.into(),
+            name: project.name.clone(),
+            description: project.description.clone(),
+            capabilities: project.capabilities.clone(),
+            platform: project.platform.clone(),
+            components: project.components.clone(),
+            source_project: Some(project.id.clone()),
+        }
+    }
+
+    /// Creates new project that isn't saved yet from the template, component identifiers are
+    /// kept as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the project.
+    pub fn to_project(&self, name: String) -> Project {
+        Project {
+            id: String::new(),
+            name,
+            description: self.description.clone(),
+            capabilities: self.capabilities.clone(),
+            platform: self.platform.clone(),
+            components: self.components.clone(),
+            revision: 0,
+            created_at: 0,
+            updated_at: 0,
+            created_by: None,
+            updated_by: None,
+            forked_from: None,
+        }
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+
+use components::component::ComponentTriggerAction;
+use components::component_property_schema::{ComponentPropertySchema, ComponentPropertyValueKind};
+use components::component_schema::ComponentSchema;
+use projects::project::Project;
+
+/// Describes changes made to the component while it was upgraded to the current version of its
+/// component schema.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ComponentUpgrade {
+    /// Identifier of the upgraded component.
+    #[serde(rename(serialize = "componentId", deserialize = "componentId"))]
+    pub component_id: String,
+    /// Type of the upgraded component.
+    #[serde(rename(serialize = "type", deserialize = "type"))]
+    pub type_name: String,
+    /// Schema version component was pinned to, if any.
+    #[serde(rename(serialize = "fromVersion", deserialize = "fromVersion"))]
+    pub from_version: Option<i64>,
+    /// Schema version component has been upgraded to.
+    #[serde(rename(serialize = "toVersion", deserialize = "toVersion"))]
+    pub to_version: i64,
+    /// Properties that are no longer declared by the schema or which values are no longer valid
+    /// and there is no default value to fall back to.
+    #[serde(rename(serialize = "droppedProperties", deserialize = "droppedProperties"))]
+    pub dropped_properties: Vec<String>,
+    /// Properties that have been set to the default value, either because they are declared by
+    /// the new schema version only or because their values are no longer valid.
+    #[serde(rename(serialize = "defaultedProperties", deserialize = "defaultedProperties"))]
+    pub defaulted_properties: Vec<String>,
+    /// Triggers that are no longer declared by the schema.
+    #[serde(rename(serialize = "droppedTriggers", deserialize = "droppedTriggers"))]
+    pub dropped_triggers: Vec<String>,
+    /// Trigger <-> trigger actions map of the actions that are no longer declared by the schema
+    /// of the target component.
+    #[serde(rename(
+        serialize = "droppedTriggerActions",
+        deserialize = "droppedTriggerActions"
+    ))]
+    pub dropped_trigger_actions: HashMap<String, Vec<ComponentTriggerAction>>,
+}
+
+impl ComponentUpgrade {
+    /// Indicates whether anything besides the pinned schema version has been changed.
+    fn is_lossless(&self) -> bool {
+        self.dropped_properties.is_empty()
+            && self.defaulted_properties.is_empty()
+            && self.dropped_triggers.is_empty()
+            && self.dropped_trigger_actions.is_empty()
+    }
+}
+
+/// Describes project upgraded to the current versions of the component schemas.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectUpgradeReport {
+    /// Upgraded project.
+    pub project: Project,
+    /// Changes made to the components, components that didn't change aren't listed.
+    pub components: Vec<ComponentUpgrade>,
+}
+
+/// Upgrades every project component to the current version of its component schema: properties
+/// and triggers that are no longer declared are dropped, values that are no longer valid are
+/// replaced with the default value (or dropped if there is none), properties declared only by the
+/// current version get their default value and trigger actions that target components can no
+/// longer perform are dropped. Components of unknown types are left intact. Returns list of the
+/// changes made to the components.
+///
+/// # Arguments
+///
+/// * `project` - Project to upgrade.
+/// * `schemas` - List of all known component schemas, with inheritance already resolved.
+/// * `schema_versions` - List of the previous versions of the component schemas that project
+/// components are pinned to, with inheritance already resolved.
+pub fn upgrade_project(
+    project: &mut Project,
+    schemas: &[ComponentSchema],
+    schema_versions: &[ComponentSchema],
+) -> Vec<ComponentUpgrade> {
+    let schemas: HashMap<&str, &ComponentSchema> = schemas
+        .iter()
+        .map(|schema| (schema.type_name.as_str(), schema))
+        .collect();
+    let component_types: HashMap<String, String> = project
+        .components
+        .iter()
+        .map(|component| (component.id.clone(), component.type_name.clone()))
+        .collect();
+
+    let mut upgrades = vec![];
+    for component in &mut project.components {
+        let schema = match schemas.get(component.type_name.as_str()) {
+            Some(schema) => schema,
+            None => continue,
+        };
+
+        // Properties can be recognized as new only if the previous version is known.
+        let previous_schema = schema_versions.iter().find(|previous_schema| {
+            previous_schema.type_name == component.type_name
+                && Some(previous_schema.version) == component.schema_version
+                && previous_schema.version != schema.version
+        });
+
+        let mut upgrade = ComponentUpgrade {
+            component_id: component.id.clone(),
+            type_name: component.type_name.clone(),
+            from_version: component.schema_version,
+            to_version: schema.version,
+            dropped_properties: vec![],
+            defaulted_properties: vec![],
+            dropped_triggers: vec![],
+            dropped_trigger_actions: HashMap::new(),
+        };
+
+        let mut properties = HashMap::new();
+        for (property_name, property_value) in component.properties.drain() {
+            let property_schema = match schema.properties.get(&property_name) {
+                Some(property_schema) => property_schema,
+                None => {
+                    upgrade.dropped_properties.push(property_name);
+                    continue;
+                }
+            };
+
+            if let Some(property_value) = valid_value(property_schema, &property_value) {
+                properties.insert(property_name, property_value);
+            } else if property_schema.default_value.is_empty() {
+                upgrade.dropped_properties.push(property_name);
+            } else {
+                properties.insert(property_name.clone(), property_schema.default_value.clone());
+                upgrade.defaulted_properties.push(property_name);
+            }
+        }
+
+        if let Some(previous_schema) = previous_schema {
+            for (property_name, property_schema) in &schema.properties {
+                if !property_schema.default_value.is_empty()
+                    && !previous_schema.properties.contains_key(property_name)
+                    && !properties.contains_key(property_name)
+                {
+                    properties.insert(property_name.clone(), property_schema.default_value.clone());
+                    upgrade.defaulted_properties.push(property_name.clone());
+                }
+            }
+        }
+
+        component.properties = properties;
+
+        let dropped_triggers: Vec<String> = component
+            .triggers
+            .keys()
+            .filter(|trigger_name| !schema.triggers.contains_key(*trigger_name))
+            .cloned()
+            .collect();
+        for trigger_name in &dropped_triggers {
+            component.triggers.remove(trigger_name);
+        }
+        upgrade.dropped_triggers = dropped_triggers;
+
+        for (trigger_name, trigger_actions) in &mut component.triggers {
+            // Actions that target unknown components are left for validation to report.
+            let (kept_actions, dropped_actions): (Vec<_>, Vec<_>) =
+                trigger_actions.drain(..).partition(|trigger_action| {
+                    component_types
+                        .get(&trigger_action.component)
+                        .and_then(|type_name| schemas.get(type_name.as_str()))
+                        .map_or(true, |target_schema| {
+                            target_schema.actions.contains_key(&trigger_action.action)
+                        })
+                });
+
+            *trigger_actions = kept_actions;
+            if !dropped_actions.is_empty() {
+                upgrade
+                    .dropped_trigger_actions
+                    .insert(trigger_name.clone(), dropped_actions);
+            }
+        }
+
+        component.schema_version = Some(schema.version);
+
+        if upgrade.from_version != Some(upgrade.to_version) || !upgrade.is_lossless() {
+            upgrade.dropped_properties.sort();
+            upgrade.defaulted_properties.sort();
+            upgrade.dropped_triggers.sort();
+            upgrades.push(upgrade);
+        }
+    }
+
+    upgrades
+}
+
+/// Returns value in canonical form if it's valid for the property, `Component`-kind properties
+/// are checked by the validation since they depend on the other components.
+fn valid_value(property_schema: &ComponentPropertySchema, value: &str) -> Option<String> {
+    match property_schema.kind {
+        ComponentPropertyValueKind::Predefined(ref options) => options
+            .iter()
+            .find(|option| option.type_name == value)
+            .map(|option| option.type_name.clone()),
+        ref kind => kind.normalize_value(value).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::upgrade_project;
+    use components::component::{Component, ComponentTriggerAction};
+    use components::component_property_schema::{
+        ComponentPropertyRange, ComponentPropertySchema, ComponentPropertyValueKind,
+    };
+    use components::component_schema::{ComponentAction, ComponentSchema, ComponentTrigger};
+    use test_fixtures;
+
+    fn schema(type_name: &str, version: i64) -> ComponentSchema {
+        let mut schema = test_fixtures::schema(type_name);
+        schema.version = version;
+        schema
+    }
+
+    fn add_property(
+        schema: &mut ComponentSchema,
+        name: &str,
+        default_value: &str,
+        kind: ComponentPropertyValueKind,
+    ) {
+        schema.properties.insert(
+            name.to_string(),
+            ComponentPropertySchema {
+                type_name: name.to_string(),
+                name: name.to_string(),
+                description: String::new(),
+                default_value: default_value.to_string(),
+                kind,
+            },
+        );
+    }
+
+    fn component(id: &str, type_name: &str, properties: &[(&str, &str)]) -> Component {
+        let mut component = test_fixtures::component(id, type_name, properties);
+        component.schema_version = Some(1);
+        component
+    }
+
+    #[test]
+    fn components_should_be_upgraded_to_current_schema_version() {
+        let brightness_range = ComponentPropertyRange {
+            min: Some(0.0),
+            max: Some(100.0),
+            step: None,
+        };
+
+        let mut led_v1 = schema("led", 1);
+        add_property(&mut led_v1, "size", "", ComponentPropertyValueKind::Custom);
+        add_property(
+            &mut led_v1,
+            "brightness",
+            "",
+            ComponentPropertyValueKind::Custom,
+        );
+        led_v1.actions.insert(
+            "blink".to_string(),
+            ComponentAction {
+                type_name: "blink".to_string(),
+                name: "Blink".to_string(),
+                description: String::new(),
+            },
+        );
+
+        let mut led_v2 = schema("led", 2);
+        add_property(
+            &mut led_v2,
+            "brightness",
+            "50",
+            ComponentPropertyValueKind::Integer(brightness_range.clone()),
+        );
+        add_property(
+            &mut led_v2,
+            "interval",
+            "1s",
+            ComponentPropertyValueKind::Duration(ComponentPropertyRange::default()),
+        );
+
+        let mut button = schema("button", 1);
+        button.triggers.insert(
+            "pressed".to_string(),
+            ComponentTrigger {
+                type_name: "pressed".to_string(),
+                name: "Pressed".to_string(),
+                description: String::new(),
+            },
+        );
+
+        let mut button_component = component("button-1", "button", &[]);
+        button_component.triggers.insert(
+            "pressed".to_string(),
+            vec![ComponentTriggerAction {
+                action: "blink".to_string(),
+                component: "led-1".to_string(),
+            }],
+        );
+        button_component
+            .triggers
+            .insert("released".to_string(), vec![]);
+
+        let mut project = test_fixtures::project(
+            "project-1",
+            "project",
+            vec![
+                component("led-1", "led", &[("size", "5mm"), ("brightness", "high")]),
+                button_component,
+                component("motor-1", "motor", &[]),
+            ],
+        );
+
+        let upgrades = upgrade_project(&mut project, &[led_v2, button], &[led_v1]);
+        assert_eq!(upgrades.len(), 2);
+
+        assert_eq!(upgrades[0].component_id, "led-1");
+        assert_eq!(upgrades[0].from_version, Some(1));
+        assert_eq!(upgrades[0].to_version, 2);
+        assert_eq!(upgrades[0].dropped_properties, vec!["size"]);
+        assert_eq!(
+            upgrades[0].defaulted_properties,
+            vec!["brightness", "interval"]
+        );
+
+        let led = &project.components[0];
+        assert_eq!(led.schema_version, Some(2));
+        assert_eq!(led.properties.len(), 2);
+        assert_eq!(led.properties["brightness"], "50");
+        assert_eq!(led.properties["interval"], "1s");
+
+        assert_eq!(upgrades[1].component_id, "button-1");
+        assert_eq!(upgrades[1].from_version, Some(1));
+        assert_eq!(upgrades[1].dropped_triggers, vec!["released"]);
+        assert_eq!(upgrades[1].dropped_trigger_actions["pressed"].len(), 1);
+        assert!(project.components[1].triggers["pressed"].is_empty());
+
+        // Components of unknown types are left intact.
+        assert_eq!(project.components[2].schema_version, Some(1));
+    }
+}
@@ -0,0 +1,59 @@
+use projects::project::{Project, ProjectSummary};
+
+/// Number of milliseconds in one day.
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Describes project that has been moved to the trash and can still be restored.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrashedProject {
+    /// Project as it was at the time of deletion.
+    pub project: Project,
+    /// Number of milliseconds elapsed since Unix epoch when project was moved to the trash.
+    #[serde(rename(serialize = "deletedAt", deserialize = "deletedAt"))]
+    pub deleted_at: i64,
+    /// Name of the user who deleted the project, if known.
+    #[serde(rename(serialize = "deletedBy", deserialize = "deletedBy"))]
+    pub deleted_by: Option<String>,
+}
+
+impl TrashedProject {
+    /// Returns summary of the trashed project that doesn't include project components.
+    ///
+    /// # Arguments
+    ///
+    /// * `retention_days` - Number of days trashed projects are kept before they are purged.
+    pub fn summary(&self, retention_days: u32) -> TrashedProjectSummary {
+        TrashedProjectSummary {
+            project: self.project.summary(),
+            deleted_at: self.deleted_at,
+            deleted_by: self.deleted_by.clone(),
+            expires_at: self.deleted_at + i64::from(retention_days) * DAY_MS,
+        }
+    }
+}
+
+/// Describes trashed project without project components, used to list trash content.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrashedProjectSummary {
+    /// Summary of the trashed project.
+    pub project: ProjectSummary,
+    /// Number of milliseconds elapsed since Unix epoch when project was moved to the trash.
+    #[serde(rename(serialize = "deletedAt", deserialize = "deletedAt"))]
+    pub deleted_at: i64,
+    /// Name of the user who deleted the project, if known.
+    #[serde(rename(serialize = "deletedBy", deserialize = "deletedBy"))]
+    pub deleted_by: Option<String>,
+    /// Number of milliseconds elapsed since Unix epoch when project will be purged.
+    #[serde(rename(serialize = "expiresAt", deserialize = "expiresAt"))]
+    pub expires_at: i64,
+}
+
+/// Returns the moment projects trashed before which are expired and should be purged.
+///
+/// # Arguments
+///
+/// * `now` - Number of milliseconds elapsed since Unix epoch.
+/// * `retention_days` - Number of days trashed projects are kept before they are purged.
+pub fn expiration_threshold(now: i64, retention_days: u32) -> i64 {
+    now - i64::from(retention_days) * DAY_MS
+}
@@ -0,0 +1,306 @@
+use std::fmt;
+
+use actix_web::{http, HttpResponse, ResponseError};
+use failure::{Error, Fail};
+use serde_json;
+
+use super::revision_etag;
+use json_patch::patch_error::PatchError;
+use validation::validation_error::{ValidationError, ValidationErrors};
+
+/// Describes all errors that API can respond with.
+#[derive(Debug)]
+pub enum ApiError {
+    /// Request is malformed.
+    BadRequest(String),
+    /// Request requires authentication, but credentials are missing or not valid.
+    Unauthorized(String),
+    /// Requested operation is not allowed.
+    Forbidden(String),
+    /// Requested resource doesn't exist.
+    NotFound(String),
+    /// Request conflicts with the current state of the resource.
+    Conflict(String),
+    /// Conditional request header is missing.
+    PreconditionRequired(String),
+    /// Resource has been modified since the revision client relied upon.
+    RevisionMismatch {
+        /// Human-readable description of the mismatch.
+        detail: String,
+        /// Current revision of the resource.
+        current_revision: i64,
+    },
+    /// Request is well-formed, but can't be processed.
+    UnprocessableEntity(String),
+    /// Entity didn't pass validation.
+    Validation(ValidationErrors),
+    /// Database operation failed.
+    Database(Error),
+    /// Request to the BOM API provider failed.
+    Bom(Error),
+    /// Schematic generation or export failed.
+    Schematic(Error),
+    /// Unexpected internal failure.
+    Internal(Error),
+    /// Request has been rejected by actix before it reached route handler (e.g. payload is too
+    /// large or method isn't supported), status is the one chosen by actix.
+    Rejected {
+        /// Status code of the rejected request.
+        status: http::StatusCode,
+        /// Human-readable description of the rejection.
+        detail: String,
+    },
+}
+
+/// Problem details response body, see [RFC 7807](https://tools.ietf.org/html/rfc7807).
+#[derive(Serialize, Debug)]
+struct ProblemDetails<'a> {
+    /// URI reference that identifies the problem type.
+    #[serde(rename(serialize = "type"))]
+    type_name: &'a str,
+    /// Short, human-readable summary of the problem type.
+    title: &'a str,
+    /// HTTP status code.
+    status: u16,
+    /// Human-readable explanation specific to this occurrence of the problem.
+    detail: String,
+    /// Unique identifier of the failed request that is also included in the server logs.
+    #[serde(
+        rename(serialize = "requestId"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    request_id: Option<&'a str>,
+    /// List of validation errors, if problem is caused by the failed validation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    errors: Option<&'a [ValidationError]>,
+}
+
+impl ApiError {
+    /// Returns status code, problem type and title that correspond to the error.
+    fn problem_type(&self) -> (http::StatusCode, &'static str, &'static str) {
+        match self {
+            ApiError::BadRequest(_) => (
+                http::StatusCode::BAD_REQUEST,
+                "/problems/bad-request",
+                "Bad request",
+            ),
+            ApiError::Unauthorized(_) => (
+                http::StatusCode::UNAUTHORIZED,
+                "/problems/unauthorized",
+                "Unauthorized",
+            ),
+            ApiError::Forbidden(_) => (
+                http::StatusCode::FORBIDDEN,
+                "/problems/forbidden",
+                "Forbidden",
+            ),
+            ApiError::NotFound(_) => (
+                http::StatusCode::NOT_FOUND,
+                "/problems/not-found",
+                "Resource not found",
+            ),
+            ApiError::Conflict(_) => (
+                http::StatusCode::CONFLICT,
+                "/problems/conflict",
+                "Resource conflict",
+            ),
+            ApiError::PreconditionRequired(_) => (
+                http::StatusCode::PRECONDITION_REQUIRED,
+                "/problems/precondition-required",
+                "Precondition required",
+            ),
+            ApiError::RevisionMismatch { .. } => (
+                http::StatusCode::PRECONDITION_FAILED,
+                "/problems/revision-mismatch",
+                "Resource has been modified",
+            ),
+            ApiError::UnprocessableEntity(_) => (
+                http::StatusCode::UNPROCESSABLE_ENTITY,
+                "/problems/unprocessable-entity",
+                "Unprocessable entity",
+            ),
+            ApiError::Validation(_) => (
+                http::StatusCode::UNPROCESSABLE_ENTITY,
+                "/problems/validation-failed",
+                "Validation failed",
+            ),
+            ApiError::Database(_) => (
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                "/problems/database-error",
+                "Database error",
+            ),
+            ApiError::Bom(_) => (
+                http::StatusCode::BAD_GATEWAY,
+                "/problems/bom-provider-error",
+                "BOM provider error",
+            ),
+            ApiError::Schematic(_) => (
+                http::StatusCode::BAD_GATEWAY,
+                "/problems/schematic-provider-error",
+                "Schematic provider error",
+            ),
+            ApiError::Internal(_) => (
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                "/problems/internal-error",
+                "Internal server error",
+            ),
+            // Status code alone describes the problem, see RFC 7807 section 4.2.
+            ApiError::Rejected { status, .. } => (
+                *status,
+                "about:blank",
+                status.canonical_reason().unwrap_or("Request rejected"),
+            ),
+        }
+    }
+
+    /// Returns detail message that is safe to expose to the client, details of the server side
+    /// failures are only logged.
+    fn detail(&self) -> String {
+        match self {
+            ApiError::BadRequest(detail)
+            | ApiError::Unauthorized(detail)
+            | ApiError::Forbidden(detail)
+            | ApiError::NotFound(detail)
+            | ApiError::Conflict(detail)
+            | ApiError::PreconditionRequired(detail)
+            | ApiError::RevisionMismatch { detail, .. }
+            | ApiError::UnprocessableEntity(detail)
+            | ApiError::Rejected { detail, .. } => detail.clone(),
+            ApiError::Validation(errors) => errors.to_string(),
+            ApiError::Database(_) => "Database operation failed.".to_string(),
+            ApiError::Bom(_) => "Request to the BOM API provider failed.".to_string(),
+            ApiError::Schematic(_) => "Schematic couldn't be generated.".to_string(),
+            ApiError::Internal(_) => "Unexpected error occurred.".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ApiError::Database(err)
+            | ApiError::Bom(err)
+            | ApiError::Schematic(err)
+            | ApiError::Internal(err) => write!(f, "{}: {}", self.problem_type().2, err),
+            _ => write!(f, "{}: {}", self.problem_type().2, self.detail()),
+        }
+    }
+}
+
+impl Fail for ApiError {}
+
+impl ApiError {
+    /// Logs the error and builds problem details response for the request with the passed
+    /// identifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - Identifier assigned to the failed request once it arrived.
+    pub fn problem_response(&self, request_id: &str) -> HttpResponse {
+        if self.problem_type().0.is_server_error() {
+            error!("Request {} failed: {:?}", request_id, self);
+        } else {
+            info!("Request {} failed: {}", request_id, self);
+        }
+
+        self.build_problem_response(Some(request_id))
+    }
+
+    fn build_problem_response(&self, request_id: Option<&str>) -> HttpResponse {
+        let (status, type_name, title) = self.problem_type();
+        let problem = ProblemDetails {
+            type_name,
+            title,
+            status: status.as_u16(),
+            detail: self.detail(),
+            request_id,
+            errors: match self {
+                ApiError::Validation(errors) => Some(errors.errors.as_slice()),
+                _ => None,
+            },
+        };
+
+        let mut response = HttpResponse::build(status);
+        response.content_type("application/problem+json");
+
+        if let ApiError::RevisionMismatch {
+            current_revision, ..
+        } = self
+        {
+            response.header(http::header::ETAG, revision_etag(*current_revision));
+        }
+
+        if let ApiError::Unauthorized(_) = self {
+            response.header(http::header::WWW_AUTHENTICATE, "Bearer");
+        }
+
+        response.body(serde_json::to_string(&problem).unwrap_or_default())
+    }
+}
+
+impl ResponseError for ApiError {
+    /// Identifier of the request isn't known here, so `RequestId` middleware replaces this
+    /// response with the one built by `problem_response`.
+    fn error_response(&self) -> HttpResponse {
+        self.build_problem_response(None)
+    }
+}
+
+/// Storage is the main source of untyped errors in the route handlers, errors from the other
+/// sources should be mapped to the corresponding variant explicitly.
+impl From<Error> for ApiError {
+    fn from(err: Error) -> Self {
+        ApiError::Database(err)
+    }
+}
+
+impl From<ValidationErrors> for ApiError {
+    fn from(errors: ValidationErrors) -> Self {
+        ApiError::Validation(errors)
+    }
+}
+
+impl From<PatchError> for ApiError {
+    fn from(err: PatchError) -> Self {
+        ApiError::UnprocessableEntity(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApiError;
+    use actix_web::{http, Body, ResponseError};
+    use failure::err_msg;
+    use serde_json::{self, Value};
+
+    #[test]
+    fn error_response_should_be_problem_details() {
+        let response = ApiError::RevisionMismatch {
+            detail: "Project has been modified".to_string(),
+            current_revision: 3,
+        }.problem_response("request-1");
+        assert_eq!(response.status(), http::StatusCode::PRECONDITION_FAILED);
+        assert_eq!(
+            response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "application/problem+json"
+        );
+        assert_eq!(response.headers().get(http::header::ETAG).unwrap(), "\"3\"");
+
+        let problem: Value = match response.body() {
+            Body::Binary(body) => serde_json::from_slice(body.as_ref()).unwrap(),
+            body => panic!("Unexpected response body {:?}", body),
+        };
+        assert_eq!(problem["requestId"], "request-1");
+        assert_eq!(problem["type"], "/problems/revision-mismatch");
+    }
+
+    #[test]
+    fn server_errors_should_not_expose_details() {
+        let error = ApiError::Database(err_msg("Connection string is user:password@host"));
+        assert_eq!(error.detail(), "Database operation failed.");
+        assert_eq!(
+            error.error_response().status(),
+            http::StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
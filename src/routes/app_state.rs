@@ -0,0 +1,14 @@
+use bom::bom_provider::BomProvider;
+use db::storage::Storage;
+use schematic::schematic_provider::SchematicProvider;
+
+/// State available to all route handlers.
+pub struct AppState {
+    pub database: Box<dyn Storage>,
+    pub bom_provider: BomProvider,
+    pub schematic_provider: SchematicProvider,
+    /// Number of days trashed projects are kept before they are purged.
+    pub trash_retention_days: u32,
+    /// Token that grants access to the catalog administration API, API is disabled if not set.
+    pub admin_token: Option<String>,
+}
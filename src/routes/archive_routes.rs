@@ -0,0 +1,148 @@
+use actix_web::middleware::cors::CorsBuilder;
+use actix_web::{http, HttpRequest, HttpResponse};
+use bytes::Bytes;
+use serde_json;
+
+use super::api_error::ApiError;
+use super::app_state::AppState;
+use super::catalog_routes::{effective_component_schemas, pinned_component_schemas};
+use super::project_routes::{get_project, insert_project, project_change, validate_project};
+use super::{flag_query_param, path_param, read_mode, revision_etag};
+use projects::project_archive::{
+    ArchiveFileTooLarge, ProjectArchive, ProjectImportReport, MAX_ARCHIVE_SIZE,
+};
+use utils::current_timestamp;
+
+pub fn setup(app: &mut CorsBuilder<AppState>) {
+    app.resource("/project/{id}/export", |r| {
+        // Generated schematic and BOM are included only if requested with `schematic=true` and
+        // `bom=true` query parameters since both require requests to the external services.
+        r.get()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(req, "id")?;
+                let include_schematic = flag_query_param(req, "schematic")?;
+                let include_bom = flag_query_param(req, "bom")?;
+
+                // Archive includes schemas in the versions project components are pinned to.
+                let project = get_project(req, &project_id)?;
+                let mut schemas = effective_component_schemas(req)?;
+                schemas.extend(pinned_component_schemas(req, &project)?);
+                let mut archive = ProjectArchive::new(project, &schemas, current_timestamp());
+
+                if include_schematic {
+                    archive.schematic = Some(
+                        req.state()
+                            .schematic_provider
+                            .get(archive.project.clone())
+                            .map_err(ApiError::Schematic)?,
+                    );
+                }
+
+                if include_bom {
+                    let mut mpns: Vec<String> = archive
+                        .schemas
+                        .iter()
+                        .filter_map(|schema| schema.mpn.clone())
+                        .collect();
+                    mpns.sort();
+                    mpns.dedup();
+                    let parts = req
+                        .state()
+                        .bom_provider
+                        .find_parts(mpns)
+                        .map_err(ApiError::Bom)?;
+                    archive.bom = Some(
+                        serde_json::to_value(parts)
+                            .map_err(|err| ApiError::Internal(err.into()))?,
+                    );
+                }
+
+                let data = archive.write().map_err(ApiError::Internal)?;
+
+                Ok(HttpResponse::Ok()
+                    .content_type("application/zip")
+                    .header(
+                        http::header::CONTENT_DISPOSITION,
+                        format!("attachment; filename=\"{}.zip\"", project_id),
+                    ).body(Bytes::from(data)))
+            })
+    }).resource("/projects/import", |r| {
+        // Project keeps its original identifier unless it's already taken by another project
+        // (including trashed ones), in which case new identifier is assigned. Archive can be much
+        // larger than the default payload limit.
+        r.post().with_config(
+            |(req, body): (HttpRequest<AppState>, Bytes)| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let archive = match ProjectArchive::read(&body) {
+                    Ok(archive) => archive,
+                    // Archive that is too large once unpacked is valid, but can't be processed.
+                    Err(err) => match err.downcast::<ArchiveFileTooLarge>() {
+                        Ok(err) => return Err(ApiError::UnprocessableEntity(err.to_string())),
+                        Err(err) => {
+                            return Err(ApiError::BadRequest(format!("Invalid archive: {}", err)))
+                        }
+                    },
+                };
+
+                // Components pinned to the previous schema versions need exactly these versions.
+                let mut schemas = effective_component_schemas(&req)?;
+                schemas.extend(pinned_component_schemas(&req, &archive.project)?);
+                let missing_schemas = archive.missing_schemas(&schemas);
+                if !missing_schemas.is_empty() {
+                    return Err(ApiError::UnprocessableEntity(format!(
+                        "Project uses component schemas that don't exist: {}.",
+                        missing_schemas.join(", ")
+                    )));
+                }
+
+                let mut project = archive.project;
+                let original_id = project.id.clone();
+
+                let database = &req.state().database;
+                let id_taken = original_id.is_empty()
+                    || database.get_project(&original_id)?.is_some()
+                    || database
+                        .get_trashed_projects(read_mode(&req)?)?
+                        .iter()
+                        .any(|trashed_project| trashed_project.project.id == original_id);
+                if id_taken {
+                    project.id = String::new();
+                }
+
+                // Project this one has been cloned from may not exist on this server.
+                project.forked_from = None;
+
+                validate_project(&req, &mut project)?;
+
+                let mut change = project_change(&req);
+                if change.message.is_none() {
+                    change.message =
+                        Some(format!("Imported from archive of project {}", original_id));
+                }
+
+                // Identifier may still be taken by the project imported concurrently.
+                let project = match database.insert_project(project.clone(), change.clone())? {
+                    Some(project) => project,
+                    None => {
+                        project.id = String::new();
+                        insert_project(&req, project, change)?
+                    }
+                };
+                Ok(HttpResponse::Created()
+                    .header(http::header::LOCATION, format!("/project/{}", project.id))
+                    .header(http::header::ETAG, revision_etag(project.revision))
+                    .json(ProjectImportReport {
+                        id_remapped: project.id != original_id,
+                        project: project.summary(),
+                        original_id,
+                    }))
+            },
+            |config| {
+                config.1.limit(MAX_ARCHIVE_SIZE as usize);
+            },
+        )
+    });
+}
@@ -0,0 +1,30 @@
+use actix_web::middleware::cors::CorsBuilder;
+use actix_web::HttpRequest;
+
+use super::api_error::ApiError;
+use super::app_state::AppState;
+use super::{json_handler, path_param};
+
+pub fn setup(app: &mut CorsBuilder<AppState>) {
+    app.resource("/bom/part/{uid}", |r| {
+        r.get().f(|req: &HttpRequest<AppState>| {
+            let part_uid: String = path_param(req, "uid")?;
+            json_handler(req, || {
+                req.state()
+                    .bom_provider
+                    .get_part(part_uid)
+                    .map_err(ApiError::Bom)
+            })
+        })
+    }).resource("/bom/parts/{mpn}", |r| {
+        r.get().f(|req: &HttpRequest<AppState>| {
+            let mpn: String = path_param(req, "mpn")?;
+            json_handler(req, || {
+                req.state()
+                    .bom_provider
+                    .find_parts(mpn.split(',').collect())
+                    .map_err(ApiError::Bom)
+            })
+        })
+    });
+}
@@ -0,0 +1,559 @@
+use actix_web::middleware::cors::CorsBuilder;
+use actix_web::{http, HttpRequest, HttpResponse};
+use bytes::Bytes;
+use failure::err_msg;
+
+use super::api_error::ApiError;
+use super::app_state::AppState;
+use super::{
+    authorize_admin, flag_query_param, json_handler, parse_json_body, path_param, read_mode,
+};
+use components::component_group::ComponentGroup;
+use components::component_json_schema::{component_json_schema, component_json_schema_bundle};
+use components::component_schema::ComponentSchema;
+use components::component_schema_inheritance::{
+    dependent_schemas, resolve_schema, resolve_schemas,
+};
+use db::storage::{ReadMode, ReplaceResult};
+use projects::project::Project;
+use validation::catalog_validator::{validate_component_group, validate_component_schema};
+
+/// Queries component group with the specified type, fails if there is no such group.
+fn get_component_group(
+    req: &HttpRequest<AppState>,
+    type_name: &str,
+) -> Result<ComponentGroup, ApiError> {
+    req.state()
+        .database
+        .get_component_groups(read_mode(req)?)?
+        .into_iter()
+        .find(|group| group.type_name == type_name)
+        .ok_or_else(|| ApiError::NotFound(format!("Component group `{}` not found", type_name)))
+}
+
+/// Resolves inheritance of the component schemas one by one, so that schema that can't be
+/// resolved (e.g. because its parent couldn't be read) doesn't make the rest of them unavailable.
+/// Such schemas are logged and skipped, unless `strict=true` query parameter is set. Stored
+/// schemas are validated before they are saved, so failed resolution is an internal error.
+///
+/// # Arguments
+///
+/// * `req` - Request the schemas are resolved for.
+/// * `schemas_to_resolve` - Component schemas to resolve, current or previous versions.
+/// * `schemas` - List of all known component schemas as they are stored.
+fn resolve_component_schemas(
+    req: &HttpRequest<AppState>,
+    schemas_to_resolve: &[ComponentSchema],
+    schemas: &[ComponentSchema],
+) -> Result<Vec<ComponentSchema>, ApiError> {
+    let is_strict = read_mode(req)? == ReadMode::Strict;
+
+    let mut resolved_schemas = vec![];
+    for schema in schemas_to_resolve {
+        match resolve_schema(schema, schemas) {
+            Ok(resolved_schema) => resolved_schemas.push(resolved_schema),
+            Err(err) if is_strict => return Err(ApiError::Internal(err_msg(err))),
+            Err(err) => warn!(
+                "Version {} of component schema `{}` is skipped: {}",
+                schema.version, schema.type_name, err
+            ),
+        }
+    }
+
+    Ok(resolved_schemas)
+}
+
+/// Queries all component schemas and resolves their inheritance, see
+/// `resolve_component_schemas`.
+pub fn effective_component_schemas(
+    req: &HttpRequest<AppState>,
+) -> Result<Vec<ComponentSchema>, ApiError> {
+    let schemas = req
+        .state()
+        .database
+        .get_component_schemas(read_mode(req)?)?;
+    resolve_component_schemas(req, &schemas, &schemas)
+}
+
+/// Queries component schema with the specified type and resolves its inheritance, fails if there
+/// is no such schema or it can't be resolved.
+fn get_effective_component_schema(
+    req: &HttpRequest<AppState>,
+    type_name: &str,
+) -> Result<ComponentSchema, ApiError> {
+    let schemas = req
+        .state()
+        .database
+        .get_component_schemas(read_mode(req)?)?;
+    let schema = schemas
+        .iter()
+        .find(|schema| schema.type_name == type_name)
+        .ok_or_else(|| ApiError::NotFound(format!("Component schema `{}` not found", type_name)))?;
+
+    resolve_schema(schema, &schemas).map_err(|err| ApiError::Internal(err_msg(err)))
+}
+
+/// Queries previous versions of the component schemas that project components are pinned to and
+/// resolves their inheritance against the current versions of the parents and mixins. Components
+/// pinned to the current or unknown versions are skipped.
+pub fn pinned_component_schemas(
+    req: &HttpRequest<AppState>,
+    project: &Project,
+) -> Result<Vec<ComponentSchema>, ApiError> {
+    let database = &req.state().database;
+    let schemas = database.get_component_schemas(read_mode(req)?)?;
+
+    let mut pinned_schemas: Vec<ComponentSchema> = vec![];
+    for component in &project.components {
+        let version = match component.schema_version {
+            Some(version) => version,
+            None => continue,
+        };
+
+        let is_known = schemas
+            .iter()
+            .chain(pinned_schemas.iter())
+            .any(|schema| schema.type_name == component.type_name && schema.version == version);
+        if is_known {
+            continue;
+        }
+
+        if let Some(schema) =
+            database.get_component_schema_version(&component.type_name, version)?
+        {
+            pinned_schemas
+                .push(resolve_schema(&schema, &schemas).map_err(ApiError::UnprocessableEntity)?);
+        }
+    }
+
+    Ok(pinned_schemas)
+}
+
+/// Queries all versions of the component schema with the specified type, either as they are
+/// stored or with inheritance resolved against the current versions of the parents and mixins,
+/// depending on the `raw` query parameter. Fails if there is no such schema.
+fn get_component_schema_versions(
+    req: &HttpRequest<AppState>,
+    type_name: &str,
+) -> Result<Vec<ComponentSchema>, ApiError> {
+    let database = &req.state().database;
+    let versions = database.get_component_schema_versions(type_name, read_mode(req)?)?;
+    if versions.is_empty() {
+        return Err(ApiError::NotFound(format!(
+            "Component schema `{}` not found",
+            type_name
+        )));
+    }
+
+    if flag_query_param(req, "raw")? {
+        return Ok(versions);
+    }
+
+    let schemas = database.get_component_schemas(read_mode(req)?)?;
+    resolve_component_schemas(req, &versions, &schemas)
+}
+
+/// Queries component schemas either as they are stored or with resolved inheritance, depending
+/// on the `raw` query parameter.
+fn requested_component_schemas(
+    req: &HttpRequest<AppState>,
+) -> Result<Vec<ComponentSchema>, ApiError> {
+    if flag_query_param(req, "raw")? {
+        Ok(req
+            .state()
+            .database
+            .get_component_schemas(read_mode(req)?)?)
+    } else {
+        effective_component_schemas(req)
+    }
+}
+
+/// Queries component schema with the specified type either as it's stored or with resolved
+/// inheritance, depending on the `raw` query parameter. Fails if there is no such schema.
+fn get_component_schema(
+    req: &HttpRequest<AppState>,
+    type_name: &str,
+) -> Result<ComponentSchema, ApiError> {
+    if !flag_query_param(req, "raw")? {
+        return get_effective_component_schema(req, type_name);
+    }
+
+    req.state()
+        .database
+        .get_component_schemas(read_mode(req)?)?
+        .into_iter()
+        .find(|schema| schema.type_name == type_name)
+        .ok_or_else(|| ApiError::NotFound(format!("Component schema `{}` not found", type_name)))
+}
+
+/// Makes sure that the rest of the stored schemas are compatible with the schema that is about
+/// to be saved: all parents and mixins exist, there are no cycles and property kinds aren't
+/// changed by the overrides.
+fn validate_schema_inheritance(
+    req: &HttpRequest<AppState>,
+    schema: &ComponentSchema,
+) -> Result<(), ApiError> {
+    let mut schemas = req
+        .state()
+        .database
+        .get_component_schemas(read_mode(req)?)?;
+    match schemas
+        .iter_mut()
+        .find(|existing_schema| existing_schema.type_name == schema.type_name)
+    {
+        Some(existing_schema) => *existing_schema = schema.clone(),
+        None => schemas.push(schema.clone()),
+    }
+
+    resolve_schemas(&schemas)
+        .map(|_| ())
+        .map_err(ApiError::UnprocessableEntity)
+}
+
+/// Saves component schema only if the stored one still has the expected version (or doesn't
+/// exist yet if `expected_version` is `None`), so that concurrent updates aren't lost.
+fn save_component_schema(
+    req: &HttpRequest<AppState>,
+    schema: &ComponentSchema,
+    expected_version: Option<i64>,
+) -> Result<(), ApiError> {
+    match req
+        .state()
+        .database
+        .save_component_schema(schema.clone(), expected_version)?
+    {
+        ReplaceResult::Replaced(_) => Ok(()),
+        ReplaceResult::NotFound => Err(ApiError::NotFound(format!(
+            "Component schema `{}` not found",
+            schema.type_name
+        ))),
+        ReplaceResult::Conflict(_) if expected_version.is_none() => {
+            Err(ApiError::Conflict(format!(
+                "Component schema `{}` already exists, use PUT /component-schemas/{} to update \
+                 it.",
+                schema.type_name, schema.type_name
+            )))
+        }
+        ReplaceResult::Conflict(current_version) => Err(ApiError::Conflict(format!(
+            "Component schema `{}` has been modified concurrently, current version is {}, but {} \
+             was expected.",
+            schema.type_name,
+            current_version,
+            expected_version.unwrap_or_default()
+        ))),
+    }
+}
+
+/// Makes sure that type of the entity from the request body matches the type from the URL, type
+/// can be omitted in the body.
+fn match_path_type(body_type_name: &mut String, type_name: &str) -> Result<(), ApiError> {
+    if body_type_name.is_empty() {
+        *body_type_name = type_name.to_string();
+    } else if body_type_name != type_name {
+        return Err(ApiError::BadRequest(format!(
+            "Type `{}` doesn't match type `{}` from the URL.",
+            body_type_name, type_name
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn setup(app: &mut CorsBuilder<AppState>) {
+    app.resource("/component-groups", |r| {
+        r.get().f(|req: &HttpRequest<AppState>| {
+            json_handler(req, || {
+                req.state()
+                    .database
+                    .get_component_groups(read_mode(req)?)
+                    .map_err(ApiError::from)
+            })
+        });
+
+        r.post().with(
+            |(req, body): (HttpRequest<AppState>, Bytes)| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                authorize_admin(&req)?;
+
+                let group: ComponentGroup = parse_json_body(&body)?;
+                let database = &req.state().database;
+                if database
+                    .get_component_groups(read_mode(&req)?)?
+                    .iter()
+                    .any(|existing_group| existing_group.type_name == group.type_name)
+                {
+                    return Err(ApiError::Conflict(format!(
+                        "Component group `{}` already exists, use PUT /component-groups/{} to \
+                         update it.",
+                        group.type_name, group.type_name
+                    )));
+                }
+
+                validate_component_group(
+                    &group,
+                    &database.get_component_schemas(read_mode(&req)?)?,
+                )?;
+                database.save_component_group(group.clone())?;
+
+                Ok(HttpResponse::Created()
+                    .header(
+                        http::header::LOCATION,
+                        format!("/component-groups/{}", group.type_name),
+                    ).json(group))
+            },
+        )
+    }).resource("/component-groups/{type}", |r| {
+        r.get().f(|req: &HttpRequest<AppState>| {
+            json_handler(req, || {
+                let type_name: String = path_param(req, "type")?;
+                get_component_group(req, &type_name)
+            })
+        });
+
+        r.put().with(
+            |(req, body): (HttpRequest<AppState>, Bytes)| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                authorize_admin(&req)?;
+
+                let type_name: String = path_param(&req, "type")?;
+                get_component_group(&req, &type_name)?;
+
+                let mut group: ComponentGroup = parse_json_body(&body)?;
+                match_path_type(&mut group.type_name, &type_name)?;
+
+                let database = &req.state().database;
+                validate_component_group(
+                    &group,
+                    &database.get_component_schemas(read_mode(&req)?)?,
+                )?;
+                database.save_component_group(group.clone())?;
+
+                Ok(HttpResponse::Ok().json(group))
+            },
+        );
+
+        r.delete()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                authorize_admin(req)?;
+
+                let type_name: String = path_param(req, "type")?;
+                if req.state().database.delete_component_group(&type_name)? {
+                    Ok(HttpResponse::NoContent().finish())
+                } else {
+                    Err(ApiError::NotFound(format!(
+                        "Component group `{}` not found",
+                        type_name
+                    )))
+                }
+            })
+    }).resource("/component-schemas", |r| {
+        // Schemas are returned with resolved inheritance unless `raw=true` is requested.
+        r.get().f(|req: &HttpRequest<AppState>| {
+            json_handler(req, || requested_component_schemas(req))
+        });
+
+        r.post().with(
+            |(req, body): (HttpRequest<AppState>, Bytes)| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                authorize_admin(&req)?;
+
+                // Versions are assigned by the server, new schema always starts with version 1.
+                let mut schema: ComponentSchema = parse_json_body(&body)?;
+                schema.version = 1;
+
+                validate_component_schema(&schema)?;
+                validate_schema_inheritance(&req, &schema)?;
+                save_component_schema(&req, &schema, None)?;
+
+                Ok(HttpResponse::Created()
+                    .header(
+                        http::header::LOCATION,
+                        format!("/component-schemas/{}", schema.type_name),
+                    ).json(schema))
+            },
+        )
+    }).resource("/component-schemas/json-schema", |r| {
+        // Registered before `/component-schemas/{type}` so that it isn't treated as schema type.
+        r.get().f(|req: &HttpRequest<AppState>| {
+            json_handler(req, || {
+                effective_component_schemas(req)
+                    .map(|schemas| component_json_schema_bundle(&schemas))
+            })
+        })
+    }).resource("/component-schemas/{type}", |r| {
+        r.get().f(|req: &HttpRequest<AppState>| {
+            json_handler(req, || {
+                let type_name: String = path_param(req, "type")?;
+                get_component_schema(req, &type_name)
+            })
+        });
+
+        r.put().with(
+            |(req, body): (HttpRequest<AppState>, Bytes)| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                authorize_admin(&req)?;
+
+                let type_name: String = path_param(&req, "type")?;
+                let database = &req.state().database;
+                // Existence is checked against the stored schemas, so that schema can be fixed even
+                // if inheritance of the stored schemas can't be resolved.
+                let current_version = database
+                    .get_component_schemas(read_mode(&req)?)?
+                    .into_iter()
+                    .find(|existing_schema| existing_schema.type_name == type_name)
+                    .map(|existing_schema| existing_schema.version)
+                    .ok_or_else(|| {
+                        ApiError::NotFound(format!("Component schema `{}` not found", type_name))
+                    })?;
+
+                // Every update creates a new version, previous one is kept for the components
+                // that are pinned to it.
+                let mut schema: ComponentSchema = parse_json_body(&body)?;
+                match_path_type(&mut schema.type_name, &type_name)?;
+                schema.version = current_version + 1;
+
+                validate_component_schema(&schema)?;
+                validate_schema_inheritance(&req, &schema)?;
+                // Schema could have been updated since its version has been read above.
+                save_component_schema(&req, &schema, Some(current_version))?;
+
+                Ok(HttpResponse::Ok().json(schema))
+            },
+        );
+
+        // Schema can't be deleted while there are projects (including trashed ones), component
+        // groups or other schemas that use it.
+        r.delete()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                authorize_admin(req)?;
+
+                let type_name: String = path_param(req, "type")?;
+                let database = &req.state().database;
+
+                let project_ids = database.get_component_type_usages(&type_name)?;
+                if !project_ids.is_empty() {
+                    return Err(ApiError::Conflict(format!(
+                        "Component schema `{}` is used by projects: {}.",
+                        type_name,
+                        project_ids.join(", ")
+                    )));
+                }
+
+                let group_types: Vec<String> = database
+                    .get_component_groups(read_mode(req)?)?
+                    .into_iter()
+                    .filter(|group| group.items.contains(&type_name))
+                    .map(|group| group.type_name)
+                    .collect();
+                if !group_types.is_empty() {
+                    return Err(ApiError::Conflict(format!(
+                        "Component schema `{}` is used by component groups: {}.",
+                        type_name,
+                        group_types.join(", ")
+                    )));
+                }
+
+                let schema_types = dependent_schemas(
+                    &type_name,
+                    &database.get_component_schemas(read_mode(req)?)?,
+                );
+                if !schema_types.is_empty() {
+                    return Err(ApiError::Conflict(format!(
+                        "Component schema `{}` is used as a parent or mixin by component schemas: \
+                         {}.",
+                        type_name,
+                        schema_types.join(", ")
+                    )));
+                }
+
+                if database.delete_component_schema(&type_name)? {
+                    Ok(HttpResponse::NoContent().finish())
+                } else {
+                    Err(ApiError::NotFound(format!(
+                        "Component schema `{}` not found",
+                        type_name
+                    )))
+                }
+            })
+    }).resource("/component-schemas/{type}/versions", |r| {
+        // The most recent versions go first.
+        r.get().f(|req: &HttpRequest<AppState>| {
+            json_handler(req, || {
+                let type_name: String = path_param(req, "type")?;
+                get_component_schema_versions(req, &type_name)
+            })
+        })
+    }).resource("/component-schemas/{type}/versions/{version}", |r| {
+        r.get().f(|req: &HttpRequest<AppState>| {
+            json_handler(req, || {
+                let type_name: String = path_param(req, "type")?;
+                let version: i64 = path_param(req, "version")?;
+
+                get_component_schema_versions(req, &type_name)?
+                    .into_iter()
+                    .find(|schema| schema.version == version)
+                    .ok_or_else(|| {
+                        ApiError::NotFound(format!(
+                            "Version {} of component schema `{}` not found",
+                            version, type_name
+                        ))
+                    })
+            })
+        })
+    }).resource("/component-schemas/{type}/json-schema", |r| {
+        r.get().f(|req: &HttpRequest<AppState>| {
+            json_handler(req, || {
+                let type_name: String = path_param(req, "type")?;
+                let schema = get_effective_component_schema(req, &type_name)?;
+
+                // Components of the abstract types can't exist, so there is nothing to describe.
+                if schema.is_abstract {
+                    return Err(ApiError::NotFound(format!(
+                        "Component schema `{}` is abstract and has no JSON Schema",
+                        type_name
+                    )));
+                }
+
+                Ok(component_json_schema(
+                    &schema,
+                    &effective_component_schemas(req)?,
+                ))
+            })
+        })
+    }).resource("/project-capabilities", |r| {
+        r.get().f(|req: &HttpRequest<AppState>| {
+            json_handler(req, || {
+                req.state()
+                    .database
+                    .get_project_capabilities(read_mode(req)?)
+                    .map_err(ApiError::from)
+            })
+        })
+    }).resource("/project-capability-groups", |r| {
+        r.get().f(|req: &HttpRequest<AppState>| {
+            json_handler(req, || {
+                req.state()
+                    .database
+                    .get_project_capability_groups(read_mode(req)?)
+                    .map_err(ApiError::from)
+            })
+        })
+    }).resource("/project-platforms", |r| {
+        r.get().f(|req: &HttpRequest<AppState>| {
+            json_handler(req, || {
+                req.state()
+                    .database
+                    .get_project_platforms(read_mode(req)?)
+                    .map_err(ApiError::from)
+            })
+        })
+    });
+}
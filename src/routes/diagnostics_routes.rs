@@ -0,0 +1,33 @@
+use actix_web::middleware::cors::CorsBuilder;
+use actix_web::{HttpRequest, HttpResponse};
+
+use super::api_error::ApiError;
+use super::app_state::AppState;
+use super::{authorize_admin, json_handler};
+
+pub fn setup(app: &mut CorsBuilder<AppState>) {
+    app.resource("/diagnostics/read-failures", |r| {
+        // Lists stored documents that couldn't be read and have been skipped or failed requests.
+        r.get().f(|req: &HttpRequest<AppState>| {
+            json_handler(req, || {
+                authorize_admin(req)?;
+                req.state()
+                    .database
+                    .get_read_failures()
+                    .map_err(ApiError::from)
+            })
+        });
+
+        // Forgets recorded failures, e.g. once broken documents are fixed.
+        r.delete()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                authorize_admin(req)?;
+
+                req.state().database.clear_read_failures()?;
+
+                Ok(HttpResponse::NoContent().finish())
+            })
+    });
+}
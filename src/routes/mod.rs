@@ -0,0 +1,187 @@
+use actix_web::dev::FromParam;
+use actix_web::middleware::cors::Cors;
+use actix_web::{http, App, HttpRequest, HttpResponse, Json};
+use bytes::Bytes;
+use serde;
+use serde_json;
+
+pub mod api_error;
+pub mod app_state;
+pub mod archive_routes;
+pub mod bom_routes;
+pub mod catalog_routes;
+pub mod diagnostics_routes;
+pub mod project_routes;
+pub mod request_id;
+pub mod schematic_routes;
+pub mod template_routes;
+pub mod trash_routes;
+
+use self::api_error::ApiError;
+use self::app_state::AppState;
+use self::request_id::RequestId;
+use db::storage::ReadMode;
+
+/// Handles requests to the routes that don't exist.
+fn unknown_resource(req: &HttpRequest<AppState>) -> Result<HttpResponse, ApiError> {
+    info!("Request received: {}", req.path());
+
+    Err(ApiError::NotFound(format!(
+        "Resource {} not found.",
+        req.path()
+    )))
+}
+
+/// Creates application that serves all API routes with the passed state. Every request gets an
+/// identifier and every failure, including requests to the unknown routes, is returned as problem
+/// details.
+pub fn create_app(state: AppState) -> App<AppState> {
+    let mut app = Cors::for_app(
+        App::with_state(state)
+            .middleware(RequestId)
+            .default_resource(|r| r.f(unknown_resource)),
+    );
+
+    app.allowed_origin("http://localhost:4200")
+        .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE"])
+        .allowed_headers(vec![
+            http::header::AUTHORIZATION,
+            http::header::ACCEPT,
+            http::header::CONTENT_TYPE,
+            http::header::IF_MATCH,
+            http::header::HeaderName::from_static("x-user"),
+        ]).expose_headers(vec![
+            http::header::LOCATION,
+            http::header::ETAG,
+            http::header::CONTENT_DISPOSITION,
+            http::header::HeaderName::from_static("x-request-id"),
+            http::header::HeaderName::from_static("x-total-count"),
+        ]);
+
+    catalog_routes::setup(&mut app);
+    project_routes::setup(&mut app);
+    trash_routes::setup(&mut app);
+    template_routes::setup(&mut app);
+    archive_routes::setup(&mut app);
+    diagnostics_routes::setup(&mut app);
+    bom_routes::setup(&mut app);
+    schematic_routes::setup(&mut app);
+
+    app.register()
+}
+
+/// Logs received request and serializes content returned by `content_retriever` as JSON.
+pub fn json_handler<F, T, E>(
+    request: &HttpRequest<AppState>,
+    content_retriever: F,
+) -> Result<Json<T>, ApiError>
+where
+    F: FnOnce() -> Result<T, E>,
+    T: serde::Serialize,
+    E: Into<ApiError>,
+{
+    info!("Request received: {}", request.path());
+
+    Ok(Json(content_retriever().map_err(Into::into)?))
+}
+
+/// Extracts parameter with the specified name from the request path.
+pub fn path_param<T>(request: &HttpRequest<AppState>, name: &str) -> Result<T, ApiError>
+where
+    T: FromParam,
+{
+    request
+        .match_info()
+        .query(name)
+        .map_err(|_| ApiError::BadRequest(format!("Path parameter `{}` is not valid.", name)))
+}
+
+/// Parses optional boolean query parameter, missing parameter is treated as `false`.
+pub fn flag_query_param(req: &HttpRequest<AppState>, name: &str) -> Result<bool, ApiError> {
+    match req.query().get(name).map(|value| value.as_str()) {
+        None | Some("false") => Ok(false),
+        Some("true") => Ok(true),
+        Some(value) => Err(ApiError::BadRequest(format!(
+            "Query parameter `{}` should be either `true` or `false`, but got `{}`.",
+            name, value
+        ))),
+    }
+}
+
+/// Returns mode the storage should use to read collections for the request: documents that can't
+/// be read are skipped unless `strict=true` query parameter is passed.
+pub fn read_mode(req: &HttpRequest<AppState>) -> Result<ReadMode, ApiError> {
+    Ok(if flag_query_param(req, "strict")? {
+        ReadMode::Strict
+    } else {
+        ReadMode::Lenient
+    })
+}
+
+/// Deserializes JSON request body.
+pub fn parse_json_body<T>(body: &Bytes) -> Result<T, ApiError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    serde_json::from_slice(body)
+        .map_err(|err| ApiError::BadRequest(format!("Request body is not valid JSON: {}", err)))
+}
+
+/// Returns name of the user who sent the request, it's taken from the `X-User` header. The header
+/// isn't authenticated, so the name is only informational and must not be used for access control.
+pub fn request_user(request: &HttpRequest<AppState>) -> Option<String> {
+    request
+        .headers()
+        .get("X-User")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Makes sure that request is authorized to use administration API, admin token should be sent
+/// in the `Authorization: Bearer <token>` header.
+pub fn authorize_admin(request: &HttpRequest<AppState>) -> Result<(), ApiError> {
+    let admin_token = match request.state().admin_token {
+        Some(ref admin_token) => admin_token,
+        None => {
+            return Err(ApiError::Forbidden(
+                "Administration API is disabled.".to_string(),
+            ))
+        }
+    };
+
+    let token = request
+        .headers()
+        .get(http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            let mut parts = value.splitn(2, ' ');
+            match (parts.next(), parts.next()) {
+                (Some(scheme), Some(token)) if scheme.eq_ignore_ascii_case("bearer") => {
+                    Some(token.trim())
+                }
+                _ => None,
+            }
+        });
+
+    match token {
+        Some(token) if constant_time_eq(token.as_bytes(), admin_token.as_bytes()) => Ok(()),
+        Some(_) => Err(ApiError::Unauthorized(
+            "Admin token is not valid.".to_string(),
+        )),
+        None => Err(ApiError::Unauthorized(
+            "Admin token is required in the `Authorization` header.".to_string(),
+        )),
+    }
+}
+
+/// Compares byte strings in time that doesn't depend on the position of the first mismatch, so
+/// that secrets can't be guessed by measuring response time.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |result, (x, y)| result | (x ^ y)) == 0
+}
+
+/// Returns value of the `ETag` header that corresponds to the specified revision.
+pub fn revision_etag(revision: i64) -> String {
+    format!("\"{}\"", revision)
+}
@@ -0,0 +1,910 @@
+use actix_web::middleware::cors::CorsBuilder;
+use actix_web::{http, HttpMessage, HttpRequest, HttpResponse, Json};
+use bytes::Bytes;
+use serde_json;
+
+use super::api_error::ApiError;
+use super::app_state::AppState;
+use super::catalog_routes::{effective_component_schemas, pinned_component_schemas};
+use super::trash_routes::purge_expired_projects;
+use super::{
+    flag_query_param, parse_json_body, path_param, read_mode, request_user, revision_etag,
+};
+use db::storage::ReplaceResult;
+use failure::Error;
+use json_patch;
+use json_patch::patch_operation::PatchOperation;
+use projects::project::Project;
+use projects::project_clone::clone_project;
+use projects::project_compatibility::{check_compatibility, PlatformCompatibility};
+use projects::project_query::{ProjectQuery, ProjectSortField};
+use projects::project_revision::{ProjectChange, ProjectRevision, ProjectRevisionSummary};
+use projects::project_search::search;
+use projects::project_upgrade::{upgrade_project, ProjectUpgradeReport};
+use validation::project_validator::ProjectValidator;
+
+/// Body of the project clone request.
+#[derive(Deserialize, Debug, Default)]
+struct CloneProjectRequest {
+    /// Name of the cloned project, defaults to the name of the original project with `Copy of`
+    /// prefix.
+    name: Option<String>,
+}
+
+/// Maximum number of the projects that can be requested at once.
+const MAX_PROJECTS_LIMIT: usize = 200;
+
+/// Parses optional numeric query parameter.
+fn numeric_query_param(req: &HttpRequest<AppState>, name: &str) -> Result<Option<usize>, ApiError> {
+    match req.query().get(name) {
+        Some(value) => value.parse::<usize>().map(Some).map_err(|_| {
+            ApiError::BadRequest(format!(
+                "Query parameter `{}` should be a non-negative integer.",
+                name
+            ))
+        }),
+        None => Ok(None),
+    }
+}
+
+/// Extracts `offset` and `limit` query parameters.
+fn pagination(
+    req: &HttpRequest<AppState>,
+    default_limit: usize,
+) -> Result<(usize, usize), ApiError> {
+    let offset = numeric_query_param(req, "offset")?.unwrap_or(0);
+    let limit = numeric_query_param(req, "limit")?.unwrap_or(default_limit);
+    if limit == 0 || limit > MAX_PROJECTS_LIMIT {
+        return Err(ApiError::BadRequest(format!(
+            "Query parameter `limit` should be between 1 and {}.",
+            MAX_PROJECTS_LIMIT
+        )));
+    }
+
+    Ok((offset, limit))
+}
+
+/// Builds projects query from the `platform`, `capabilities`, `sort`, `offset` and `limit`
+/// query parameters. Sort field can be prefixed with `-` to sort in descending order.
+fn project_query(req: &HttpRequest<AppState>) -> Result<ProjectQuery, ApiError> {
+    let query_params = req.query();
+    let mut query = ProjectQuery::default();
+
+    query.platform = query_params.get("platform").cloned();
+    if let Some(capabilities) = query_params.get("capabilities") {
+        query.capabilities = capabilities
+            .split(',')
+            .filter(|capability| !capability.is_empty())
+            .map(|capability| capability.to_string())
+            .collect();
+    }
+
+    if let Some(sort) = query_params.get("sort") {
+        query.sort_descending = sort.starts_with('-');
+        query.sort_field = match sort.trim_start_matches('-') {
+            "name" => ProjectSortField::Name,
+            "createdAt" => ProjectSortField::CreatedAt,
+            "updatedAt" => ProjectSortField::UpdatedAt,
+            field => {
+                return Err(ApiError::BadRequest(format!(
+                    "Projects can't be sorted by `{}`, supported fields are `name`, `createdAt` \
+                     and `updatedAt`.",
+                    field
+                )))
+            }
+        };
+    }
+
+    let (offset, limit) = pagination(req, query.limit)?;
+    query.offset = offset;
+    query.limit = limit;
+
+    query.include_components = match query_params.get("view").map(String::as_str) {
+        None | Some("full") => true,
+        Some("summary") => false,
+        Some(view) => {
+            return Err(ApiError::BadRequest(format!(
+                "Unknown view `{}`, supported views are `full` and `summary`.",
+                view
+            )))
+        }
+    };
+
+    Ok(query)
+}
+
+/// Parses value of the `If-Match` header into the list of revisions it matches, `*` that matches
+/// any existing revision is returned as `None`. ETags are compared strongly, so weak and foreign
+/// ETags are valid, but don't match any revision.
+fn parse_if_match(value: &str) -> Result<Option<Vec<i64>>, ApiError> {
+    let value = value.trim();
+    if value == "*" {
+        return Ok(None);
+    }
+
+    let mut revisions = vec![];
+    for etag in value
+        .split(',')
+        .map(str::trim)
+        .filter(|etag| !etag.is_empty())
+    {
+        let opaque_tag = etag.trim_start_matches("W/");
+        let is_quoted = opaque_tag.len() >= 2
+            && opaque_tag.starts_with('"')
+            && opaque_tag.ends_with('"')
+            && !opaque_tag[1..opaque_tag.len() - 1].contains('"');
+        if !is_quoted {
+            return Err(ApiError::BadRequest(
+                "If-Match header should contain `*` or a list of quoted ETags.".to_string(),
+            ));
+        }
+
+        if opaque_tag.len() == etag.len() {
+            if let Ok(revision) = opaque_tag[1..opaque_tag.len() - 1].parse::<i64>() {
+                revisions.push(revision);
+            }
+        }
+    }
+
+    Ok(Some(revisions))
+}
+
+/// Extracts revision from the `If-Match` header that is required for all conditional updates,
+/// `*` resolves to the current revision of the project and fails with `404` if it doesn't exist.
+/// Header that lists several ETags resolves to the current revision if it's one of them, and
+/// fails with `412` if none of ETags matches.
+fn if_match_revision(req: &HttpRequest<AppState>, project_id: &str) -> Result<i64, ApiError> {
+    let header_value = req.headers().get(http::header::IF_MATCH).ok_or_else(|| {
+        ApiError::PreconditionRequired("If-Match header is required.".to_string())
+    })?;
+
+    let header_value = header_value.to_str().map_err(|_| {
+        ApiError::BadRequest("If-Match header should contain a valid ETag.".to_string())
+    })?;
+
+    let revisions = match parse_if_match(header_value)? {
+        // Single revision is checked by the storage along with the update.
+        Some(ref revisions) if revisions.len() == 1 => return Ok(revisions[0]),
+        Some(revisions) => revisions,
+        None => return Ok(get_project(req, project_id)?.revision),
+    };
+
+    let current_revision = get_project(req, project_id)?.revision;
+    if revisions.contains(&current_revision) {
+        Ok(current_revision)
+    } else {
+        Err(ApiError::RevisionMismatch {
+            detail: format!(
+                "Project {} has been modified, current revision is {}, but If-Match header \
+                 doesn't match it",
+                project_id, current_revision
+            ),
+            current_revision,
+        })
+    }
+}
+
+/// Describes change made by the request: author is taken from the unauthenticated `X-User` header
+/// and optional revision message from the `message` query parameter.
+pub fn project_change(req: &HttpRequest<AppState>) -> ProjectChange {
+    ProjectChange {
+        author: request_user(req),
+        message: req.query().get("message").cloned(),
+    }
+}
+
+/// Builds `201 Created` response for the newly created project.
+pub fn created_project_response(project: Project) -> HttpResponse {
+    HttpResponse::Created()
+        .header(http::header::LOCATION, format!("/project/{}", project.id))
+        .header(http::header::ETAG, revision_etag(project.revision))
+        .json(project)
+}
+
+/// Converts result of the conditional project replace operation into HTTP response.
+fn replace_project_response(
+    project_id: &str,
+    expected_revision: i64,
+    replace_result: Result<ReplaceResult<Project>, Error>,
+) -> Result<HttpResponse, ApiError> {
+    match replace_result? {
+        ReplaceResult::Replaced(project) => Ok(HttpResponse::Ok()
+            .header(http::header::LOCATION, format!("/project/{}", project.id))
+            .header(http::header::ETAG, revision_etag(project.revision))
+            .json(project)),
+        ReplaceResult::NotFound => Err(ApiError::NotFound(format!(
+            "Project with id {} not found",
+            project_id
+        ))),
+        ReplaceResult::Conflict(current_revision) => Err(ApiError::RevisionMismatch {
+            detail: format!(
+                "Project {} has been modified, current revision is {}, but {} was expected",
+                project_id, current_revision, expected_revision
+            ),
+            current_revision,
+        }),
+    }
+}
+
+/// Creates validator that knows about all component schemas (including the versions project
+/// components are pinned to), capabilities and platforms known to the storage.
+fn project_validator(
+    req: &HttpRequest<AppState>,
+    project: &Project,
+) -> Result<ProjectValidator, ApiError> {
+    let database = &req.state().database;
+    Ok(ProjectValidator::new(
+        effective_component_schemas(req)?,
+        pinned_component_schemas(req, project)?,
+        database.get_project_capabilities(read_mode(req)?)?,
+        database.get_project_platforms(read_mode(req)?)?,
+    ))
+}
+
+/// Brings values of the typed component properties into canonical form and validates project
+/// against the component schemas (either current versions or the ones components are pinned to),
+/// capabilities and platforms known to the storage. If `fillDefaults=true` query parameter is
+/// set, missing component properties are filled with the default values from the schemas first.
+pub fn validate_project(
+    req: &HttpRequest<AppState>,
+    project: &mut Project,
+) -> Result<(), ApiError> {
+    let validator = project_validator(req, project)?;
+
+    if flag_query_param(req, "fillDefaults")? {
+        validator.fill_defaults(project);
+    }
+
+    validator.normalize(project);
+    Ok(validator.validate(project)?)
+}
+
+/// Queries project and fails with `404` if it doesn't exist.
+pub fn get_project(req: &HttpRequest<AppState>, project_id: &str) -> Result<Project, ApiError> {
+    req.state()
+        .database
+        .get_project(project_id)?
+        .ok_or_else(|| ApiError::NotFound(format!("Project with id {} not found", project_id)))
+}
+
+/// Inserts new project and fails with `409` if project with the same identifier already exists.
+pub fn insert_project(
+    req: &HttpRequest<AppState>,
+    project: Project,
+    change: ProjectChange,
+) -> Result<Project, ApiError> {
+    let project_id = project.id.clone();
+    req.state()
+        .database
+        .insert_project(project, change)?
+        .ok_or_else(|| ApiError::Conflict(format!("Project with id {} already exists", project_id)))
+}
+
+/// Queries project with all missing component properties filled with the default values from the
+/// component schemas, fails with `404` if it doesn't exist. Stored project isn't modified.
+pub fn get_project_with_defaults(
+    req: &HttpRequest<AppState>,
+    project_id: &str,
+) -> Result<Project, ApiError> {
+    let mut project = get_project(req, project_id)?;
+    project_validator(req, &project)?.fill_defaults(&mut project);
+
+    Ok(project)
+}
+
+/// Queries specific project revision and fails with `404` if it doesn't exist.
+fn get_project_revision(
+    req: &HttpRequest<AppState>,
+    project_id: &str,
+    revision: i64,
+) -> Result<ProjectRevision, ApiError> {
+    req.state()
+        .database
+        .get_project_revision(project_id, revision)?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "Revision {} of project {} not found",
+                revision, project_id
+            ))
+        })
+}
+
+/// Applies either JSON Patch (RFC 6902) or JSON Merge Patch (RFC 7396) document to the project,
+/// depending on the content type of the request.
+fn patch_project(
+    req: &HttpRequest<AppState>,
+    project: Project,
+    patch: &Bytes,
+) -> Result<Project, ApiError> {
+    let document = serde_json::to_value(project).map_err(|err| ApiError::Internal(err.into()))?;
+    let patched_document = if req.content_type() == "application/merge-patch+json" {
+        let patch: serde_json::Value = parse_json_body(patch)?;
+        json_patch::merge_patch::merge(&document, &patch)
+    } else {
+        let patch: Vec<PatchOperation> = parse_json_body(patch)?;
+        json_patch::apply::apply(&document, &patch)?
+    };
+
+    serde_json::from_value(patched_document).map_err(|err| {
+        ApiError::UnprocessableEntity(format!("Patched project is not valid: {}", err))
+    })
+}
+
+pub fn setup(app: &mut CorsBuilder<AppState>) {
+    app.resource("/projects", |r| {
+        // Total number of the projects matching the query is returned in `X-Total-Count` header.
+        r.get()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let query = project_query(req)?;
+                let page = req
+                    .state()
+                    .database
+                    .find_projects(&query, read_mode(req)?)?;
+
+                let mut response = HttpResponse::Ok();
+                response.header("X-Total-Count", page.total.to_string());
+
+                Ok(if query.include_components {
+                    response.json(page.items)
+                } else {
+                    response.json(page.items.iter().map(Project::summary).collect::<Vec<_>>())
+                })
+            })
+    }).resource("/projects/search", |r| {
+        // Search results are paginated with `offset` and `limit` query parameters as well.
+        r.get()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let query = req
+                    .query()
+                    .get("q")
+                    .map(|query| query.trim().to_string())
+                    .unwrap_or_default();
+                if query.is_empty() {
+                    return Err(ApiError::BadRequest(
+                        "Query parameter `q` is required.".to_string(),
+                    ));
+                }
+
+                let (offset, limit) = pagination(req, 20)?;
+
+                // Storage narrows down the projects first, so that not every project is ranked.
+                let projects = req
+                    .state()
+                    .database
+                    .search_projects(&query, read_mode(req)?)?;
+                let results = search(&projects, &query);
+
+                Ok(HttpResponse::Ok()
+                    .header("X-Total-Count", results.len().to_string())
+                    .json(
+                        results
+                            .into_iter()
+                            .skip(offset)
+                            .take(limit)
+                            .collect::<Vec<_>>(),
+                    ))
+            })
+    }).resource("/project", |r| {
+        // POST is used only to create new projects, identifier is always assigned by the server.
+        r.post().with(
+            |(req, body): (HttpRequest<AppState>, Bytes)| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let mut project_to_save: Project = parse_json_body(&body)?;
+                if !project_to_save.id.is_empty() {
+                    return Err(ApiError::BadRequest(format!(
+                        "New project shouldn't have an id, use PUT /project/{} to update existing \
+                         project.",
+                        project_to_save.id
+                    )));
+                }
+
+                // Only projects created with `/project/{id}/clone` can be linked to the original.
+                project_to_save.forked_from = None;
+
+                validate_project(&req, &mut project_to_save)?;
+
+                let change = project_change(&req);
+                Ok(created_project_response(insert_project(
+                    &req,
+                    project_to_save,
+                    change,
+                )?))
+            },
+        )
+    }).resource("/project/{id}", |r| {
+        r.get()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(req, "id")?;
+                let project = get_project(req, &project_id)?;
+
+                Ok(HttpResponse::Ok()
+                    .header(http::header::ETAG, revision_etag(project.revision))
+                    .json(project))
+            });
+
+        // Project can only be replaced if client proves that it has seen the latest revision.
+        r.put().with(
+            |(req, body): (HttpRequest<AppState>, Bytes)| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(&req, "id")?;
+                let expected_revision = if_match_revision(&req, &project_id)?;
+
+                let mut project_to_save: Project = parse_json_body(&body)?;
+                if !project_to_save.id.is_empty() && project_to_save.id != project_id {
+                    return Err(ApiError::BadRequest(format!(
+                        "Project id {} doesn't match id {} from the URL.",
+                        project_to_save.id, project_id
+                    )));
+                }
+
+                project_to_save.id = project_id.clone();
+
+                validate_project(&req, &mut project_to_save)?;
+
+                replace_project_response(
+                    &project_id,
+                    expected_revision,
+                    req.state().database.replace_project(
+                        project_to_save,
+                        expected_revision,
+                        project_change(&req),
+                    ),
+                )
+            },
+        );
+
+        // Patch is applied to the latest project revision that client must specify in `If-Match`.
+        r.method(http::Method::PATCH).with(
+            |(req, body): (HttpRequest<AppState>, Bytes)| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(&req, "id")?;
+                let expected_revision = if_match_revision(&req, &project_id)?;
+
+                let current_project = get_project(&req, &project_id)?;
+                if current_project.revision != expected_revision {
+                    return replace_project_response(
+                        &project_id,
+                        expected_revision,
+                        Ok(ReplaceResult::Conflict(current_project.revision)),
+                    );
+                }
+
+                let mut project_to_save = patch_project(&req, current_project, &body)?;
+                if project_to_save.id != project_id {
+                    return Err(ApiError::UnprocessableEntity(
+                        "Project id can't be changed.".to_string(),
+                    ));
+                }
+
+                validate_project(&req, &mut project_to_save)?;
+
+                replace_project_response(
+                    &project_id,
+                    expected_revision,
+                    req.state().database.replace_project(
+                        project_to_save,
+                        expected_revision,
+                        project_change(&req),
+                    ),
+                )
+            },
+        );
+
+        r.delete()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(req, "id")?;
+                purge_expired_projects(req)?;
+
+                // Deleted projects are moved to the trash and can be restored until they expire.
+                if req
+                    .state()
+                    .database
+                    .trash_project(&project_id, project_change(req))?
+                {
+                    Ok(HttpResponse::NoContent().finish())
+                } else {
+                    Err(ApiError::NotFound(format!(
+                        "Project with id {} not found",
+                        project_id
+                    )))
+                }
+            })
+    }).resource("/project/{id}/clone", |r| {
+        // Request body is optional and can only specify name of the cloned project.
+        r.post().with(
+            |(req, body): (HttpRequest<AppState>, Bytes)| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(&req, "id")?;
+                let clone_request: CloneProjectRequest = if body.is_empty() {
+                    CloneProjectRequest::default()
+                } else {
+                    parse_json_body(&body)?
+                };
+
+                let project = get_project(&req, &project_id)?;
+                let name = clone_request
+                    .name
+                    .unwrap_or_else(|| format!("Copy of {}", project.name));
+                let cloned_project =
+                    clone_project(&project, &effective_component_schemas(&req)?, name);
+
+                let mut change = project_change(&req);
+                if change.message.is_none() {
+                    change.message = Some(format!("Cloned from project {}", project_id));
+                }
+
+                Ok(created_project_response(insert_project(
+                    &req,
+                    cloned_project,
+                    change,
+                )?))
+            },
+        )
+    }).resource("/project/{id}/with-defaults", |r| {
+        // Project is returned as if it was saved with `fillDefaults=true`, but isn't modified.
+        r.get()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(req, "id")?;
+                let project = get_project_with_defaults(req, &project_id)?;
+
+                Ok(HttpResponse::Ok()
+                    .header(http::header::ETAG, revision_etag(project.revision))
+                    .json(project))
+            })
+    }).resource("/project/{id}/upgrade", |r| {
+        // Upgraded project is saved as a new revision, so the same revision check as for the
+        // regular project update is performed. With `dryRun=true` only the report is returned.
+        r.post()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(req, "id")?;
+                let dry_run = flag_query_param(req, "dryRun")?;
+                let expected_revision = if dry_run {
+                    None
+                } else {
+                    Some(if_match_revision(req, &project_id)?)
+                };
+
+                let mut project = get_project(req, &project_id)?;
+                let expected_revision = expected_revision.unwrap_or(project.revision);
+                if project.revision != expected_revision {
+                    return replace_project_response(
+                        &project_id,
+                        expected_revision,
+                        Ok(ReplaceResult::Conflict(project.revision)),
+                    );
+                }
+
+                let schema_versions = pinned_component_schemas(req, &project)?;
+                let components = upgrade_project(
+                    &mut project,
+                    &effective_component_schemas(req)?,
+                    &schema_versions,
+                );
+
+                // Nothing is saved if all components already use current schema versions.
+                if dry_run || components.is_empty() {
+                    return Ok(HttpResponse::Ok()
+                        .header(http::header::ETAG, revision_etag(project.revision))
+                        .json(ProjectUpgradeReport {
+                            project,
+                            components,
+                        }));
+                }
+
+                validate_project(req, &mut project)?;
+
+                let mut change = project_change(req);
+                if change.message.is_none() {
+                    change.message = Some("Upgraded to the current component schemas".to_string());
+                }
+
+                match req
+                    .state()
+                    .database
+                    .replace_project(project, expected_revision, change)?
+                {
+                    ReplaceResult::Replaced(project) => Ok(HttpResponse::Ok()
+                        .header(http::header::ETAG, revision_etag(project.revision))
+                        .json(ProjectUpgradeReport {
+                            project,
+                            components,
+                        })),
+                    replace_result => {
+                        replace_project_response(&project_id, expected_revision, Ok(replace_result))
+                    }
+                }
+            })
+    }).resource("/project/{id}/compatibility", |r| {
+        r.get().f(
+            |req: &HttpRequest<AppState>| -> Result<Json<Vec<PlatformCompatibility>>, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(req, "id")?;
+                let project = get_project(req, &project_id)?;
+                let platforms = req
+                    .state()
+                    .database
+                    .get_project_platforms(read_mode(req)?)?;
+
+                Ok(Json(check_compatibility(&project, &platforms)))
+            },
+        )
+    }).resource("/project/{id}/revisions", |r| {
+        r.get().f(
+            |req: &HttpRequest<AppState>| -> Result<Json<Vec<ProjectRevisionSummary>>, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(req, "id")?;
+                let revisions = req
+                    .state()
+                    .database
+                    .get_project_revisions(&project_id, read_mode(req)?)?;
+
+                // Every existing project has at least one revision, so empty history means that
+                // project doesn't exist.
+                if revisions.is_empty() {
+                    return Err(ApiError::NotFound(format!(
+                        "Project with id {} not found",
+                        project_id
+                    )));
+                }
+
+                Ok(Json(
+                    revisions.iter().map(ProjectRevision::summary).collect(),
+                ))
+            },
+        )
+    }).resource("/project/{id}/revisions/{revision}", |r| {
+        r.get().f(
+            |req: &HttpRequest<AppState>| -> Result<Json<ProjectRevision>, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(req, "id")?;
+                let revision: i64 = path_param(req, "revision")?;
+
+                Ok(Json(get_project_revision(req, &project_id, revision)?))
+            },
+        )
+    }).resource("/project/{id}/revisions/{from}/diff/{to}", |r| {
+        r.get().f(
+            |req: &HttpRequest<AppState>| -> Result<Json<Vec<PatchOperation>>, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(req, "id")?;
+                let from: i64 = path_param(req, "from")?;
+                let to: i64 = path_param(req, "to")?;
+
+                let from_project = get_project_revision(req, &project_id, from)?.project;
+                let to_project = get_project_revision(req, &project_id, to)?.project;
+
+                let from_project = serde_json::to_value(from_project)
+                    .map_err(|err| ApiError::Internal(err.into()))?;
+                let to_project = serde_json::to_value(to_project)
+                    .map_err(|err| ApiError::Internal(err.into()))?;
+
+                Ok(Json(json_patch::diff::diff(&from_project, &to_project)))
+            },
+        )
+    }).resource("/project/{id}/revisions/{revision}/restore", |r| {
+        // Restored revision becomes a new head revision of the project, so the same revision
+        // check as for the regular project update is performed.
+        r.post()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(req, "id")?;
+                let revision: i64 = path_param(req, "revision")?;
+                let expected_revision = if_match_revision(req, &project_id)?;
+
+                let mut project_to_restore =
+                    get_project_revision(req, &project_id, revision)?.project;
+                project_to_restore.id = project_id.clone();
+
+                validate_project(req, &mut project_to_restore)?;
+
+                let mut change = project_change(req);
+                if change.message.is_none() {
+                    change.message = Some(format!("Restored from revision {}", revision));
+                }
+
+                replace_project_response(
+                    &project_id,
+                    expected_revision,
+                    req.state().database.replace_project(
+                        project_to_restore,
+                        expected_revision,
+                        change,
+                    ),
+                )
+            })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_if_match;
+    use actix_web::http::{self, Method, StatusCode};
+    use actix_web::test::TestServer;
+    use actix_web::HttpMessage;
+    use projects::project::Project;
+    use projects::project_revision::ProjectChange;
+    use routes::create_app;
+    use test_fixtures::{self, project};
+
+    /// Starts server backed by the in-memory storage that already has one project, returns server
+    /// along with the identifier of that project.
+    fn test_server() -> (TestServer, String) {
+        let storage = test_fixtures::memory_storage();
+        let project = storage
+            .insert_project(project("", "project", vec![]), ProjectChange::default())
+            .unwrap()
+            .unwrap();
+
+        let server =
+            TestServer::with_factory(move || create_app(test_fixtures::app_state(storage.clone())));
+        (server, project.id)
+    }
+
+    /// Sends request with optional `If-Match` header and project as JSON body, returns status of
+    /// the response along with its `ETag` and `Location` headers.
+    fn send(
+        server: &mut TestServer,
+        method: Method,
+        path: &str,
+        if_match: Option<&str>,
+        body: Option<Project>,
+    ) -> (StatusCode, Option<String>, Option<String>) {
+        let mut request = server.client(method, path);
+        if let Some(if_match) = if_match {
+            request.header(http::header::IF_MATCH, if_match);
+        }
+        let request = match body {
+            Some(body) => request.json(body),
+            None => request.finish(),
+        }.unwrap();
+
+        let response = server.execute(request.send()).unwrap();
+        let header = |name: http::header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        (
+            response.status(),
+            header(http::header::ETAG),
+            header(http::header::LOCATION),
+        )
+    }
+
+    #[test]
+    fn if_match_should_be_parsed() {
+        assert_eq!(parse_if_match("\"3\"").unwrap(), Some(vec![3]));
+        assert_eq!(parse_if_match(" * ").unwrap(), None);
+        assert_eq!(
+            parse_if_match("\"3\", \"5\",\"7\"").unwrap(),
+            Some(vec![3, 5, 7])
+        );
+
+        // Weak and foreign ETags never match.
+        assert_eq!(parse_if_match("W/\"4\"").unwrap(), Some(vec![]));
+        assert_eq!(
+            parse_if_match("\"abc\", W/\"4\", \"5\"").unwrap(),
+            Some(vec![5])
+        );
+
+        // ETags must be quoted.
+        assert!(parse_if_match("3").is_err());
+        assert!(parse_if_match("W/4").is_err());
+        assert!(parse_if_match("\"3\", 4").is_err());
+        assert!(parse_if_match("\"3").is_err());
+    }
+
+    /// Replaces project at the `path` with the one named `updated`.
+    fn put_project(
+        server: &mut TestServer,
+        path: &str,
+        if_match: Option<&str>,
+    ) -> (StatusCode, Option<String>, Option<String>) {
+        let project = project("", "updated", vec![]);
+        send(server, Method::PUT, path, if_match, Some(project))
+    }
+
+    #[test]
+    fn project_should_be_created_without_id() {
+        let (mut server, _) = test_server();
+
+        // Identifier is always assigned by the server.
+        let (status, _, _) = send(
+            &mut server,
+            Method::POST,
+            "/project",
+            None,
+            Some(project("project-1", "project", vec![])),
+        );
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, etag, location) = send(
+            &mut server,
+            Method::POST,
+            "/project",
+            None,
+            Some(project("", "project", vec![])),
+        );
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(etag, Some("\"1\"".to_string()));
+        let location = location.unwrap();
+        assert!(location.starts_with("/project/"));
+
+        let (status, etag, _) = send(&mut server, Method::GET, &location, None, None);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(etag, Some("\"1\"".to_string()));
+    }
+
+    #[test]
+    fn unknown_project_should_not_be_found() {
+        let (mut server, _) = test_server();
+
+        let (status, _, _) = send(&mut server, Method::GET, "/project/unknown", None, None);
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _, _) = put_project(&mut server, "/project/unknown", Some("\"1\""));
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _, _) = send(&mut server, Method::DELETE, "/project/unknown", None, None);
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn project_should_be_replaced_only_if_revision_matches() {
+        let (mut server, project_id) = test_server();
+        let path = format!("/project/{}", project_id);
+
+        let (status, _, _) = put_project(&mut server, &path, None);
+        assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+
+        let (status, etag, location) = put_project(&mut server, &path, Some("\"1\""));
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(etag, Some("\"2\"".to_string()));
+        assert_eq!(location, Some(path.clone()));
+
+        // Response to the stale revision tells the current one.
+        let (status, etag, _) = put_project(&mut server, &path, Some("\"1\""));
+        assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+        assert_eq!(etag, Some("\"2\"".to_string()));
+
+        let (status, _, _) = put_project(&mut server, &path, Some("W/\"2\""));
+        assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+
+        let (status, _, _) = put_project(&mut server, &path, Some("2"));
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, etag, _) = put_project(&mut server, &path, Some("\"1\", \"2\""));
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(etag, Some("\"3\"".to_string()));
+
+        let (status, etag, _) = put_project(&mut server, &path, Some("*"));
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(etag, Some("\"4\"".to_string()));
+
+        let (status, _, _) = send(&mut server, Method::DELETE, &path, None, None);
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+}
@@ -0,0 +1,162 @@
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::http::StatusCode;
+use actix_web::middleware::{Middleware, Response, Started};
+use actix_web::{Error, HttpRequest, HttpResponse, Result};
+use failure::err_msg;
+use uuid::Uuid;
+
+use super::api_error::ApiError;
+
+/// Identifier assigned to the request once it arrives, it's kept in the request extensions.
+struct AssignedRequestId(String);
+
+/// Middleware that assigns unique identifier to every request once it arrives. Identifier is sent
+/// back in the `X-Request-Id` header and included into problem details of the failed request, so
+/// that failure reported by the client can be found in the server logs. Errors produced by actix
+/// itself (e.g. failed extractors or unsupported methods) are returned as problem details too.
+pub struct RequestId;
+
+/// Returns identifier assigned to the request by `RequestId` middleware, if any.
+pub fn request_id<S>(req: &HttpRequest<S>) -> Option<String> {
+    req.extensions()
+        .get::<AssignedRequestId>()
+        .map(|request_id| request_id.0.clone())
+}
+
+/// Converts error produced by actix itself into API error with the same status, details of the
+/// server side failures are only logged.
+fn rejection_error(status: StatusCode, err: Option<&Error>) -> ApiError {
+    let detail = match err {
+        Some(err) => err.to_string(),
+        None => status
+            .canonical_reason()
+            .unwrap_or("Request rejected")
+            .to_string(),
+    };
+
+    if status.is_server_error() {
+        ApiError::Internal(err_msg(detail))
+    } else {
+        ApiError::Rejected { status, detail }
+    }
+}
+
+/// Builds problem details response for the failed request, headers set by the other middlewares
+/// (e.g. CORS ones) to the original response are preserved.
+fn problem_response(
+    response: &HttpResponse,
+    api_error: &ApiError,
+    request_id: &str,
+) -> HttpResponse {
+    let mut problem_response = api_error.problem_response(request_id);
+    let problem_headers: Vec<HeaderName> = problem_response.headers().keys().cloned().collect();
+    for (name, value) in response.headers() {
+        if !problem_headers.contains(name) {
+            problem_response
+                .headers_mut()
+                .append(name.clone(), value.clone());
+        }
+    }
+
+    problem_response
+}
+
+impl<S> Middleware<S> for RequestId {
+    fn start(&self, req: &HttpRequest<S>) -> Result<Started> {
+        req.extensions_mut()
+            .insert(AssignedRequestId(Uuid::new_v4().to_string()));
+        Ok(Started::Done)
+    }
+
+    fn response(&self, req: &HttpRequest<S>, response: HttpResponse) -> Result<Response> {
+        let request_id = match request_id(req) {
+            Some(request_id) => request_id,
+            None => return Ok(Response::Done(response)),
+        };
+
+        let status = response.status();
+        let problem = {
+            let api_error = response
+                .error()
+                .and_then(|err| err.as_fail().downcast_ref::<ApiError>());
+            let rejection = match response.error() {
+                Some(_) if api_error.is_some() => None,
+                Some(err) => Some(rejection_error(status, Some(err))),
+                // Some of the actix failures (e.g. unsupported method) don't carry an error.
+                None if status.is_client_error() || status.is_server_error() => {
+                    Some(rejection_error(status, None))
+                }
+                None => None,
+            };
+
+            api_error
+                .or_else(|| rejection.as_ref())
+                .map(|api_error| problem_response(&response, api_error, &request_id))
+        };
+
+        let mut response = problem.unwrap_or(response);
+
+        if let Ok(request_id) = HeaderValue::from_str(&request_id) {
+            response
+                .headers_mut()
+                .insert(HeaderName::from_static("x-request-id"), request_id);
+        }
+
+        Ok(Response::Done(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::{Method, StatusCode};
+    use actix_web::test::TestServer;
+    use actix_web::HttpMessage;
+    use serde_json::{self, Value};
+
+    use routes::create_app;
+    use test_fixtures;
+
+    /// Sends request with the passed body, returns status of the response along with its
+    /// `X-Request-Id` header and problem details.
+    fn send(method: Method, path: &str, body: Vec<u8>) -> (StatusCode, String, Value) {
+        let storage = test_fixtures::memory_storage();
+        let mut server =
+            TestServer::with_factory(move || create_app(test_fixtures::app_state(storage.clone())));
+
+        let request = server.client(method, path).body(body).unwrap();
+        let response = server.execute(request.send()).unwrap();
+        let status = response.status();
+        let request_id = response
+            .headers()
+            .get("X-Request-Id")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        assert_eq!(
+            response.headers().get("Content-Type").unwrap(),
+            "application/problem+json"
+        );
+
+        let body = server.execute(response.body()).unwrap();
+        (status, request_id, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[test]
+    fn actix_errors_should_be_problem_details() {
+        let (status, request_id, problem) = send(Method::GET, "/unknown", vec![]);
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(problem["type"], "/problems/not-found");
+        assert!(!request_id.is_empty());
+        assert_eq!(problem["requestId"], request_id.as_str());
+
+        // Payload is larger than the default limit.
+        let (status, request_id, problem) = send(Method::POST, "/project", vec![b' '; 512 * 1024]);
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(problem["type"], "about:blank");
+        assert_eq!(problem["requestId"], request_id.as_str());
+
+        let (status, _, problem) = send(Method::PATCH, "/projects", vec![]);
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(problem["status"], 405);
+    }
+}
@@ -0,0 +1,52 @@
+use std::io;
+use std::path::PathBuf;
+
+use actix_web::fs::NamedFile;
+use actix_web::middleware::cors::CorsBuilder;
+use actix_web::{HttpRequest, HttpResponse};
+use bytes::Bytes;
+
+use super::api_error::ApiError;
+use super::app_state::AppState;
+use super::path_param;
+use super::project_routes::get_project_with_defaults;
+
+pub fn setup(app: &mut CorsBuilder<AppState>) {
+    app.resource("/schematic/{id}", |r| {
+        r.get()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(req, "id")?;
+                // Generator relies on complete property maps.
+                let project = get_project_with_defaults(req, &project_id)?;
+
+                let data = req
+                    .state()
+                    .schematic_provider
+                    .get(project)
+                    .map_err(ApiError::Schematic)?;
+
+                Ok(HttpResponse::Ok()
+                    .content_type("image/svg+xml")
+                    .body(Bytes::from(data)))
+            })
+    });
+
+    // Serve generated schematic files. File name is "{project-id}.fzz".
+    app.resource(r"/schematic/generated/{tail:.*}", |r| {
+        r.get()
+            .f(|req: &HttpRequest<AppState>| -> Result<NamedFile, ApiError> {
+                let mut path: PathBuf = PathBuf::from("generated/schematic");
+                path.push(path_param::<String>(req, "tail")?);
+
+                info!("Serving static file: {:?}", path);
+                NamedFile::open(&path).map_err(|err| match err.kind() {
+                    io::ErrorKind::NotFound => {
+                        ApiError::NotFound(format!("File {:?} not found", path))
+                    }
+                    _ => ApiError::Internal(err.into()),
+                })
+            })
+    });
+}
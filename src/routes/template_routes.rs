@@ -0,0 +1,167 @@
+use actix_web::middleware::cors::CorsBuilder;
+use actix_web::{http, HttpRequest, HttpResponse, Json};
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use super::api_error::ApiError;
+use super::app_state::AppState;
+use super::catalog_routes::effective_component_schemas;
+use super::project_routes::{
+    created_project_response, get_project, insert_project, project_change, validate_project,
+};
+use super::{parse_json_body, path_param, read_mode};
+use projects::project_clone::regenerate_component_ids;
+use projects::project_template::ProjectTemplate;
+
+/// Body of the request to instantiate project from the template.
+#[derive(Deserialize, Debug, Default)]
+struct InstantiateTemplateRequest {
+    /// Name of the project, defaults to the name of the template.
+    name: Option<String>,
+}
+
+/// Body of the request to create template from the project.
+#[derive(Deserialize, Debug, Default)]
+struct CreateTemplateRequest {
+    /// Name of the template, defaults to the name of the project.
+    name: Option<String>,
+    /// Description of the template, defaults to the description of the project.
+    description: Option<String>,
+}
+
+/// Deserializes optional JSON request body, empty body is treated as default value.
+fn parse_optional_json_body<T>(body: &Bytes) -> Result<T, ApiError>
+where
+    T: DeserializeOwned + Default,
+{
+    if body.is_empty() {
+        Ok(T::default())
+    } else {
+        parse_json_body(body)
+    }
+}
+
+/// Queries project template and fails with `404` if it doesn't exist.
+fn get_project_template(
+    req: &HttpRequest<AppState>,
+    template_id: &str,
+) -> Result<ProjectTemplate, ApiError> {
+    req.state()
+        .database
+        .get_project_template(template_id)?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "Project template with id {} not found",
+                template_id
+            ))
+        })
+}
+
+pub fn setup(app: &mut CorsBuilder<AppState>) {
+    app.resource("/project-templates", |r| {
+        // Templates can be filtered with `platform` and comma-separated `capabilities` query
+        // parameters, only templates that have all specified capabilities are returned.
+        r.get().f(
+            |req: &HttpRequest<AppState>| -> Result<Json<Vec<ProjectTemplate>>, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let query = req.query();
+                let platform = query.get("platform");
+                let capabilities: Vec<&str> = query
+                    .get("capabilities")
+                    .map(|capabilities| {
+                        capabilities
+                            .split(',')
+                            .filter(|capability| !capability.is_empty())
+                            .collect()
+                    }).unwrap_or_default();
+
+                Ok(Json(
+                    req.state()
+                        .database
+                        .get_project_templates(read_mode(req)?)?
+                        .into_iter()
+                        .filter(|template| {
+                            platform.map_or(true, |platform| &template.platform == platform)
+                                && capabilities.iter().all(|capability| {
+                                    template.capabilities.iter().any(|template_capability| {
+                                        template_capability == capability
+                                    })
+                                })
+                        }).collect(),
+                ))
+            },
+        )
+    }).resource("/project-templates/{id}", |r| {
+        r.get().f(
+            |req: &HttpRequest<AppState>| -> Result<Json<ProjectTemplate>, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let template_id: String = path_param(req, "id")?;
+                Ok(Json(get_project_template(req, &template_id)?))
+            },
+        )
+    }).resource("/project-templates/{id}/instantiate", |r| {
+        r.post().with(
+            |(req, body): (HttpRequest<AppState>, Bytes)| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let template_id: String = path_param(&req, "id")?;
+                let instantiate_request: InstantiateTemplateRequest =
+                    parse_optional_json_body(&body)?;
+
+                let template = get_project_template(&req, &template_id)?;
+                let name = instantiate_request
+                    .name
+                    .unwrap_or_else(|| template.name.clone());
+
+                let mut project = template.to_project(name);
+                regenerate_component_ids(
+                    &mut project.components,
+                    &effective_component_schemas(&req)?,
+                );
+
+                validate_project(&req, &mut project)?;
+
+                let mut change = project_change(&req);
+                if change.message.is_none() {
+                    change.message = Some(format!("Created from template {}", template_id));
+                }
+
+                Ok(created_project_response(insert_project(
+                    &req, project, change,
+                )?))
+            },
+        )
+    }).resource("/project/{id}/as-template", |r| {
+        r.post().with(
+            |(req, body): (HttpRequest<AppState>, Bytes)| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(&req, "id")?;
+                let create_request: CreateTemplateRequest = parse_optional_json_body(&body)?;
+
+                let project = get_project(&req, &project_id)?;
+                let mut template =
+                    ProjectTemplate::from_project(Uuid::new_v4().to_string(), &project);
+                if let Some(name) = create_request.name {
+                    template.name = name;
+                }
+                if let Some(description) = create_request.description {
+                    template.description = description;
+                }
+
+                req.state()
+                    .database
+                    .save_project_template(template.clone())?;
+
+                Ok(HttpResponse::Created()
+                    .header(
+                        http::header::LOCATION,
+                        format!("/project-templates/{}", template.id),
+                    ).json(template))
+            },
+        )
+    });
+}
@@ -0,0 +1,101 @@
+use actix_web::middleware::cors::CorsBuilder;
+use actix_web::{http, HttpRequest, HttpResponse, Json};
+
+use super::api_error::ApiError;
+use super::app_state::AppState;
+use super::{path_param, read_mode, revision_etag};
+use projects::trashed_project::{expiration_threshold, TrashedProjectSummary};
+use utils::current_timestamp;
+
+/// Permanently deletes projects that have been in the trash for longer than retention period.
+pub fn purge_expired_projects(req: &HttpRequest<AppState>) -> Result<(), ApiError> {
+    let state = req.state();
+    let purged_project_ids = state.database.purge_trashed_projects(expiration_threshold(
+        current_timestamp(),
+        state.trash_retention_days,
+    ))?;
+
+    if !purged_project_ids.is_empty() {
+        info!(
+            "Expired projects purged from the trash: {:?}",
+            purged_project_ids
+        );
+    }
+
+    Ok(())
+}
+
+pub fn setup(app: &mut CorsBuilder<AppState>) {
+    app.resource("/trash", |r| {
+        r.get().f(
+            |req: &HttpRequest<AppState>| -> Result<Json<Vec<TrashedProjectSummary>>, ApiError> {
+                info!("Request received: {}", req.path());
+
+                purge_expired_projects(req)?;
+
+                let retention_days = req.state().trash_retention_days;
+                Ok(Json(
+                    req.state()
+                        .database
+                        .get_trashed_projects(read_mode(req)?)?
+                        .iter()
+                        .map(|trashed_project| trashed_project.summary(retention_days))
+                        .collect(),
+                ))
+            },
+        );
+
+        // Empties the trash.
+        r.delete()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let purged_project_ids = req
+                    .state()
+                    .database
+                    .purge_trashed_projects(i64::max_value())?;
+                info!("Projects purged from the trash: {:?}", purged_project_ids);
+
+                Ok(HttpResponse::NoContent().finish())
+            })
+    }).resource("/trash/{id}", |r| {
+        r.delete()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(req, "id")?;
+                if req.state().database.purge_trashed_project(&project_id)? {
+                    Ok(HttpResponse::NoContent().finish())
+                } else {
+                    Err(ApiError::NotFound(format!(
+                        "Project with id {} not found in the trash",
+                        project_id
+                    )))
+                }
+            })
+    }).resource("/trash/{id}/restore", |r| {
+        r.post()
+            .f(|req: &HttpRequest<AppState>| -> Result<HttpResponse, ApiError> {
+                info!("Request received: {}", req.path());
+
+                let project_id: String = path_param(req, "id")?;
+                purge_expired_projects(req)?;
+
+                let project = req
+                    .state()
+                    .database
+                    .restore_trashed_project(&project_id)?
+                    .ok_or_else(|| {
+                        ApiError::NotFound(format!(
+                            "Project with id {} not found in the trash",
+                            project_id
+                        ))
+                    })?;
+
+                Ok(HttpResponse::Ok()
+                    .header(http::header::LOCATION, format!("/project/{}", project.id))
+                    .header(http::header::ETAG, revision_etag(project.revision))
+                    .json(project))
+            })
+    });
+}
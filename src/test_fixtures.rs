@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use url::Url;
+
+use bom::bom_provider::BomProvider;
+use components::component::Component;
+use components::component_schema::ComponentSchema;
+use db::memory_storage::MemoryStorage;
+use db::storage::Storage;
+use projects::project::Project;
+use projects::project_platform::ProjectPlatform;
+use routes::app_state::AppState;
+use schematic::schematic_provider::SchematicProvider;
+
+/// Builds component with the specified property values, without triggers and not pinned to any
+/// schema version.
+pub fn component(id: &str, type_name: &str, properties: &[(&str, &str)]) -> Component {
+    Component {
+        id: id.to_string(),
+        type_name: type_name.to_string(),
+        properties: properties
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+        triggers: HashMap::new(),
+        schema_version: None,
+    }
+}
+
+/// Builds the first version of the component schema that doesn't declare any properties, actions
+/// or triggers, schema name is the same as its type.
+pub fn schema(type_name: &str) -> ComponentSchema {
+    ComponentSchema {
+        type_name: type_name.to_string(),
+        version: 1,
+        name: type_name.to_string(),
+        description: String::new(),
+        mpn: None,
+        properties: HashMap::new(),
+        actions: HashMap::new(),
+        triggers: HashMap::new(),
+        extends: None,
+        mixins: vec![],
+        is_abstract: false,
+    }
+}
+
+/// Builds `arduino` project with the specified components that has never been saved.
+pub fn project(id: &str, name: &str, components: Vec<Component>) -> Project {
+    Project {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: String::new(),
+        capabilities: vec![],
+        platform: "arduino".to_string(),
+        components,
+        revision: 0,
+        created_at: 0,
+        updated_at: 0,
+        created_by: None,
+        updated_by: None,
+        forked_from: None,
+    }
+}
+
+/// Builds in-memory storage that knows about `arduino` platform and `led` component schema, so
+/// that projects built with `project` can be saved to it.
+pub fn memory_storage() -> MemoryStorage {
+    let storage = MemoryStorage::new();
+    storage
+        .save_project_platform(ProjectPlatform {
+            type_name: "arduino".to_string(),
+            name: "Arduino".to_string(),
+            description: String::new(),
+            capabilities: vec![],
+        }).unwrap();
+    storage.save_component_schema(schema("led"), None).unwrap();
+
+    storage
+}
+
+/// Builds state of the application that uses the passed storage, BOM and schematic providers
+/// point to the services that aren't expected to be reachable.
+pub fn app_state(database: MemoryStorage) -> AppState {
+    let unreachable_url = Url::parse("http://127.0.0.1:1").unwrap();
+    AppState {
+        database: Box::new(database),
+        bom_provider: BomProvider::new(unreachable_url.clone(), ""),
+        schematic_provider: SchematicProvider::new(
+            unreachable_url.clone(),
+            unreachable_url,
+            "generated/schematic",
+        ),
+        trash_retention_days: 30,
+        admin_token: None,
+    }
+}
@@ -0,0 +1,10 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Returns number of milliseconds elapsed since Unix epoch.
+pub fn current_timestamp() -> i64 {
+    let elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    elapsed.as_secs() as i64 * 1000 + i64::from(elapsed.subsec_millis())
+}
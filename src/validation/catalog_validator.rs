@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+
+use super::validation_error::{ValidationError, ValidationErrors};
+use components::component_group::ComponentGroup;
+use components::component_property_schema::{ComponentPropertyRange, ComponentPropertyValueKind};
+use components::component_property_value::format_number;
+use components::component_schema::ComponentSchema;
+use json_patch::pointer::escape_token;
+
+/// Component schema types that can't be used since they clash with the static segments of the
+/// `/component-schemas/...` routes (e.g. JSON Schema bundle of all component schemas).
+const RESERVED_COMPONENT_TYPES: &[&str] = &["json-schema"];
+
+/// Checks that component schema is self-consistent: type is set and isn't reserved, every
+/// property, action and trigger is keyed by its own type and default values of the predefined
+/// properties are among the options.
+///
+/// # Arguments
+///
+/// * `schema` - Component schema to validate.
+pub fn validate_component_schema(schema: &ComponentSchema) -> Result<(), ValidationErrors> {
+    let mut errors = vec![];
+
+    if schema.type_name.trim().is_empty() {
+        errors.push(ValidationError::new(
+            "/type",
+            "Component schema type should not be empty.",
+        ));
+    } else if RESERVED_COMPONENT_TYPES.contains(&schema.type_name.as_str()) {
+        errors.push(ValidationError::new(
+            "/type",
+            format!("Component schema type `{}` is reserved.", schema.type_name),
+        ));
+    }
+
+    for (property_name, property) in &schema.properties {
+        let property_path = format!("/properties/{}", escape_token(property_name));
+        validate_key(
+            &property_path,
+            property_name,
+            &property.type_name,
+            &mut errors,
+        );
+
+        let kind_path = format!("{}/kind", property_path);
+        match property.kind {
+            ComponentPropertyValueKind::Predefined(ref options) => {
+                if options.is_empty() {
+                    errors.push(ValidationError::new(
+                        kind_path,
+                        "Predefined property should have at least one option.",
+                    ));
+                } else if !property.default_value.is_empty()
+                    && !options
+                        .iter()
+                        .any(|option| option.type_name == property.default_value)
+                {
+                    errors.push(ValidationError::new(
+                        format!("{}/defaultValue", property_path),
+                        format!(
+                            "Default value `{}` is not one of the predefined options.",
+                            property.default_value
+                        ),
+                    ));
+                }
+            }
+            ComponentPropertyValueKind::Integer(ref range)
+            | ComponentPropertyValueKind::Float(ref range)
+            | ComponentPropertyValueKind::Duration(ref range) => {
+                validate_range(&kind_path, range, &mut errors)
+            }
+            ComponentPropertyValueKind::Quantity(ref quantity) => {
+                validate_range(&kind_path, &quantity.range, &mut errors)
+            }
+            _ => {}
+        }
+
+        // Defaults of the typed properties should be valid values of the corresponding kind.
+        if !property.default_value.is_empty() {
+            if let Err(message) = property.kind.normalize_value(&property.default_value) {
+                errors.push(ValidationError::new(
+                    format!("{}/defaultValue", property_path),
+                    message,
+                ));
+            }
+        }
+    }
+
+    for (action_name, action) in &schema.actions {
+        let action_path = format!("/actions/{}", escape_token(action_name));
+        validate_key(&action_path, action_name, &action.type_name, &mut errors);
+    }
+
+    for (trigger_name, trigger) in &schema.triggers {
+        let trigger_path = format!("/triggers/{}", escape_token(trigger_name));
+        validate_key(&trigger_path, trigger_name, &trigger.type_name, &mut errors);
+    }
+
+    ValidationErrors::into_result(errors)
+}
+
+/// Checks that component group type is set and group items are unique types of the existing
+/// non-abstract component schemas.
+///
+/// # Arguments
+///
+/// * `group` - Component group to validate.
+/// * `schemas` - List of all known component schemas.
+pub fn validate_component_group(
+    group: &ComponentGroup,
+    schemas: &[ComponentSchema],
+) -> Result<(), ValidationErrors> {
+    let mut errors = vec![];
+
+    if group.type_name.trim().is_empty() {
+        errors.push(ValidationError::new(
+            "/type",
+            "Component group type should not be empty.",
+        ));
+    }
+
+    let mut items = HashSet::new();
+    for (index, item) in group.items.iter().enumerate() {
+        let message = match schemas.iter().find(|schema| schema.type_name == *item) {
+            None => format!("Unknown component type `{}`.", item),
+            Some(schema) if schema.is_abstract => {
+                format!("Component type `{}` is abstract.", item)
+            }
+            Some(_) if !items.insert(item) => {
+                format!("Component type `{}` is listed more than once.", item)
+            }
+            Some(_) => continue,
+        };
+
+        errors.push(ValidationError::new(format!("/items/{}", index), message));
+    }
+
+    ValidationErrors::into_result(errors)
+}
+
+/// Makes sure that range minimum doesn't exceed maximum and step is positive.
+fn validate_range(path: &str, range: &ComponentPropertyRange, errors: &mut Vec<ValidationError>) {
+    if let (Some(min), Some(max)) = (range.min, range.max) {
+        if min > max {
+            errors.push(ValidationError::new(
+                path,
+                format!(
+                    "Minimum `{}` is greater than maximum `{}`.",
+                    format_number(min),
+                    format_number(max)
+                ),
+            ));
+        }
+    }
+
+    if let Some(step) = range.step {
+        if step <= 0.0 {
+            errors.push(ValidationError::new(
+                path,
+                format!("Step `{}` should be positive.", format_number(step)),
+            ));
+        }
+    }
+}
+
+/// Makes sure that map key is the same as the type of the entry it refers to.
+fn validate_key(path: &str, key: &str, type_name: &str, errors: &mut Vec<ValidationError>) {
+    if key != type_name {
+        errors.push(ValidationError::new(
+            format!("{}/type", path),
+            format!("Type `{}` doesn't match the key `{}`.", type_name, key),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_component_group, validate_component_schema};
+    use components::component_group::ComponentGroup;
+    use components::component_property_schema::{
+        ComponentPropertyPredefinedValue, ComponentPropertySchema, ComponentPropertyValueKind,
+    };
+    use test_fixtures::schema;
+    use validation::validation_error::ValidationError;
+
+    #[test]
+    fn component_schema_should_be_validated() {
+        let mut led = schema("led");
+        assert!(validate_component_schema(&led).is_ok());
+
+        led.properties.insert(
+            "color".to_string(),
+            ComponentPropertySchema {
+                type_name: "colour".to_string(),
+                name: "Color".to_string(),
+                description: String::new(),
+                default_value: "blue".to_string(),
+                kind: ComponentPropertyValueKind::Predefined(vec![
+                    ComponentPropertyPredefinedValue {
+                        type_name: "red".to_string(),
+                        name: "Red".to_string(),
+                        description: String::new(),
+                    },
+                ]),
+            },
+        );
+
+        let mut errors = validate_component_schema(&led).unwrap_err().errors;
+        errors.sort_by(|error_a, error_b| error_a.path.cmp(&error_b.path));
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::new(
+                    "/properties/color/defaultValue",
+                    "Default value `blue` is not one of the predefined options.",
+                ),
+                ValidationError::new(
+                    "/properties/color/type",
+                    "Type `colour` doesn't match the key `color`.",
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn component_schema_type_should_not_be_reserved() {
+        assert_eq!(
+            validate_component_schema(&schema("json-schema"))
+                .unwrap_err()
+                .errors,
+            vec![ValidationError::new(
+                "/type",
+                "Component schema type `json-schema` is reserved.",
+            )]
+        );
+    }
+
+    #[test]
+    fn component_group_should_reference_existing_schemas() {
+        let schemas = vec![schema("led"), schema("button")];
+        let mut group = ComponentGroup {
+            type_name: "basic".to_string(),
+            name: "Basic".to_string(),
+            description: String::new(),
+            items: vec!["led".to_string(), "button".to_string()],
+        };
+        assert!(validate_component_group(&group, &schemas).is_ok());
+
+        group.items = vec!["led".to_string(), "servo".to_string(), "led".to_string()];
+        assert_eq!(
+            validate_component_group(&group, &schemas)
+                .unwrap_err()
+                .errors,
+            vec![
+                ValidationError::new("/items/1", "Unknown component type `servo`."),
+                ValidationError::new("/items/2", "Component type `led` is listed more than once."),
+            ]
+        );
+    }
+}
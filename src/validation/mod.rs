@@ -0,0 +1,3 @@
+pub mod catalog_validator;
+pub mod project_validator;
+pub mod validation_error;
@@ -0,0 +1,704 @@
+use std::collections::{HashMap, HashSet};
+
+use super::validation_error::{ValidationError, ValidationErrors};
+use components::component::Component;
+use components::component_property_schema::ComponentPropertyValueKind;
+use components::component_schema::ComponentSchema;
+use json_patch::pointer::escape_token;
+use projects::project::Project;
+use projects::project_capability::ProjectCapability;
+use projects::project_platform::ProjectPlatform;
+
+/// Validates projects against the known component schemas, capabilities and platforms.
+pub struct ProjectValidator {
+    /// Component type <-> component schema map.
+    schemas: HashMap<String, ComponentSchema>,
+    /// (Component type, schema version) <-> previous version of the component schema map.
+    schema_versions: HashMap<(String, i64), ComponentSchema>,
+    /// Types of all known project capabilities.
+    capabilities: HashSet<String>,
+    /// Platform type <-> platform map.
+    platforms: HashMap<String, ProjectPlatform>,
+}
+
+impl ProjectValidator {
+    /// Returns a ready validator instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `schemas` - List of all known component schemas, with inheritance already resolved.
+    /// * `schema_versions` - List of the previous versions of the component schemas that project
+    /// components are pinned to, with inheritance already resolved.
+    /// * `capabilities` - List of all known project capabilities.
+    /// * `platforms` - List of all known project platforms.
+    pub fn new(
+        schemas: Vec<ComponentSchema>,
+        schema_versions: Vec<ComponentSchema>,
+        capabilities: Vec<ProjectCapability>,
+        platforms: Vec<ProjectPlatform>,
+    ) -> Self {
+        ProjectValidator {
+            schemas: schemas
+                .into_iter()
+                .map(|schema| (schema.type_name.clone(), schema))
+                .collect(),
+            schema_versions: schema_versions
+                .into_iter()
+                .map(|schema| ((schema.type_name.clone(), schema.version), schema))
+                .collect(),
+            capabilities: capabilities
+                .into_iter()
+                .map(|capability| capability.type_name)
+                .collect(),
+            platforms: platforms
+                .into_iter()
+                .map(|platform| (platform.type_name.clone(), platform))
+                .collect(),
+        }
+    }
+
+    /// Checks project platform and capabilities, and every project component against the
+    /// matching component schema and returns all found errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project to validate.
+    pub fn validate(&self, project: &Project) -> Result<(), ValidationErrors> {
+        let mut errors = vec![];
+
+        self.validate_capabilities(project, &mut errors);
+
+        let mut components: HashMap<&str, &Component> = HashMap::new();
+        for (index, component) in project.components.iter().enumerate() {
+            if components.insert(&component.id, component).is_some() {
+                errors.push(ValidationError::new(
+                    format!("/components/{}/id", index),
+                    format!("Component id `{}` is not unique.", component.id),
+                ));
+            }
+        }
+
+        for (index, component) in project.components.iter().enumerate() {
+            self.validate_component(
+                &format!("/components/{}", index),
+                component,
+                &components,
+                &mut errors,
+            );
+        }
+
+        ValidationErrors::into_result(errors)
+    }
+
+    /// Rewrites values of the typed component properties (numbers, booleans, durations, colors and
+    /// quantities) into their canonical representation. Values that can't be parsed are left
+    /// intact, so that `validate` can report them. Components that aren't pinned to any schema
+    /// version yet are pinned to the current one.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project to normalize.
+    pub fn normalize(&self, project: &mut Project) {
+        for component in &mut project.components {
+            if component.schema_version.is_none() {
+                component.schema_version = self
+                    .schemas
+                    .get(&component.type_name)
+                    .map(|schema| schema.version);
+            }
+
+            let schema = match self.pinned_schema(component) {
+                Some(schema) => schema,
+                None => continue,
+            };
+
+            for (property_name, property_value) in &mut component.properties {
+                let property_kind = match schema.properties.get(property_name) {
+                    Some(property_schema) => &property_schema.kind,
+                    None => continue,
+                };
+
+                if let Ok(normalized_value) = property_kind.normalize_value(property_value) {
+                    *property_value = normalized_value;
+                }
+            }
+        }
+    }
+
+    /// Sets every property that component schema declares, but component doesn't have, to the
+    /// default value from the schema, so that consumers always see complete property maps.
+    /// Properties with empty default value are left unset.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project which components should be completed.
+    pub fn fill_defaults(&self, project: &mut Project) {
+        for component in &mut project.components {
+            let schema = match self.pinned_schema(component) {
+                Some(schema) => schema,
+                None => continue,
+            };
+
+            for (property_name, property_schema) in &schema.properties {
+                if property_schema.default_value.is_empty()
+                    || component.properties.contains_key(property_name)
+                {
+                    continue;
+                }
+
+                let default_value = property_schema
+                    .kind
+                    .normalize_value(&property_schema.default_value)
+                    .unwrap_or_else(|_| property_schema.default_value.clone());
+                component
+                    .properties
+                    .insert(property_name.clone(), default_value);
+            }
+        }
+    }
+
+    /// Returns version of the component schema that component is pinned to, components that
+    /// aren't pinned use the current version. Returns `None` if either type or version is unknown.
+    fn pinned_schema(&self, component: &Component) -> Option<&ComponentSchema> {
+        let schema = self.schemas.get(&component.type_name)?;
+        match component.schema_version {
+            Some(version) if version != schema.version => self
+                .schema_versions
+                .get(&(component.type_name.clone(), version)),
+            _ => Some(schema),
+        }
+    }
+
+    fn validate_capabilities(&self, project: &Project, errors: &mut Vec<ValidationError>) {
+        let platform = self.platforms.get(&project.platform);
+        if platform.is_none() {
+            errors.push(ValidationError::new(
+                "/platform",
+                format!("Unknown platform `{}`.", project.platform),
+            ));
+        }
+
+        for (index, capability) in project.capabilities.iter().enumerate() {
+            let capability_path = format!("/capabilities/{}", index);
+            if !self.capabilities.contains(capability) {
+                errors.push(ValidationError::new(
+                    capability_path,
+                    format!("Unknown capability `{}`.", capability),
+                ));
+            } else if let Some(platform) = platform {
+                if !platform.capabilities.contains(capability) {
+                    errors.push(ValidationError::new(
+                        capability_path,
+                        format!(
+                            "Capability `{}` is not supported by `{}` platform.",
+                            capability, platform.type_name
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn validate_component(
+        &self,
+        path: &str,
+        component: &Component,
+        components: &HashMap<&str, &Component>,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        if !self.schemas.contains_key(&component.type_name) {
+            errors.push(ValidationError::new(
+                format!("{}/type", path),
+                format!("Unknown component type `{}`.", component.type_name),
+            ));
+            return;
+        }
+
+        let schema = match self.pinned_schema(component) {
+            Some(schema) => schema,
+            None => {
+                errors.push(ValidationError::new(
+                    format!("{}/schemaVersion", path),
+                    format!(
+                        "Version {} of `{}` component schema doesn't exist.",
+                        component.schema_version.unwrap_or_default(),
+                        component.type_name
+                    ),
+                ));
+                return;
+            }
+        };
+
+        if schema.is_abstract {
+            errors.push(ValidationError::new(
+                format!("{}/type", path),
+                format!(
+                    "Component type `{}` is abstract and can't be used directly.",
+                    component.type_name
+                ),
+            ));
+            return;
+        }
+
+        for (property_name, property_value) in &component.properties {
+            let property_path = format!("{}/properties/{}", path, escape_token(property_name));
+            let property_schema = match schema.properties.get(property_name) {
+                Some(property_schema) => property_schema,
+                None => {
+                    errors.push(ValidationError::new(
+                        property_path,
+                        format!(
+                            "Property `{}` is not declared by `{}` component schema.",
+                            property_name, schema.type_name
+                        ),
+                    ));
+                    continue;
+                }
+            };
+
+            match property_schema.kind {
+                ComponentPropertyValueKind::Custom => {}
+                ComponentPropertyValueKind::Predefined(ref options) => {
+                    if !options.iter().any(|option| option.type_name == *property_value) {
+                        errors.push(ValidationError::new(
+                            property_path,
+                            format!(
+                                "Value `{}` is not one of the predefined options: {}.",
+                                property_value,
+                                options
+                                    .iter()
+                                    .map(|option| format!("`{}`", option.type_name))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                        ));
+                    }
+                }
+                ComponentPropertyValueKind::Component(ref allowed_types) => {
+                    match components.get(property_value.as_str()) {
+                        None => errors.push(ValidationError::new(
+                            property_path,
+                            format!("Component `{}` doesn't exist.", property_value),
+                        )),
+                        Some(referenced_component)
+                            if !allowed_types.is_empty()
+                                && !allowed_types.contains(&referenced_component.type_name) =>
+                        {
+                            errors.push(ValidationError::new(
+                                property_path,
+                                format!(
+                                    "Component `{}` has type `{}`, but one of {} is expected.",
+                                    property_value,
+                                    referenced_component.type_name,
+                                    allowed_types
+                                        .iter()
+                                        .map(|type_name| format!("`{}`", type_name))
+                                        .collect::<Vec<_>>()
+                                        .join(", ")
+                                ),
+                            ))
+                        }
+                        _ => {}
+                    }
+                }
+                ref kind => {
+                    if let Err(message) = kind.normalize_value(property_value) {
+                        errors.push(ValidationError::new(property_path, message));
+                    }
+                }
+            }
+        }
+
+        for (trigger_name, trigger_actions) in &component.triggers {
+            let trigger_path = format!("{}/triggers/{}", path, escape_token(trigger_name));
+            if !schema.triggers.contains_key(trigger_name) {
+                errors.push(ValidationError::new(
+                    trigger_path,
+                    format!(
+                        "Trigger `{}` is not declared by `{}` component schema.",
+                        trigger_name, schema.type_name
+                    ),
+                ));
+                continue;
+            }
+
+            for (index, trigger_action) in trigger_actions.iter().enumerate() {
+                let action_path = format!("{}/{}", trigger_path, index);
+                let target_component = match components.get(trigger_action.component.as_str()) {
+                    Some(target_component) => target_component,
+                    None => {
+                        errors.push(ValidationError::new(
+                            format!("{}/component", action_path),
+                            format!("Component `{}` doesn't exist.", trigger_action.component),
+                        ));
+                        continue;
+                    }
+                };
+
+                // Unknown type or version of the target component is reported for that component
+                // itself.
+                if let Some(target_schema) = self.pinned_schema(target_component) {
+                    if !target_schema.actions.contains_key(&trigger_action.action) {
+                        errors.push(ValidationError::new(
+                            format!("{}/action", action_path),
+                            format!(
+                                "Action `{}` is not declared by `{}` component schema.",
+                                trigger_action.action, target_schema.type_name
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::ProjectValidator;
+    use components::component::ComponentTriggerAction;
+    use components::component_property_schema::{
+        ComponentPropertyPredefinedValue, ComponentPropertyRange, ComponentPropertySchema,
+        ComponentPropertyValueKind,
+    };
+    use components::component_schema::{ComponentAction, ComponentSchema, ComponentTrigger};
+    use projects::project_capability::ProjectCapability;
+    use projects::project_platform::ProjectPlatform;
+    use test_fixtures::{component, project, schema};
+    use validation::validation_error::ValidationError;
+
+    fn property(type_name: &str, kind: ComponentPropertyValueKind) -> ComponentPropertySchema {
+        ComponentPropertySchema {
+            type_name: type_name.to_string(),
+            name: type_name.to_string(),
+            description: String::new(),
+            default_value: String::new(),
+            kind,
+        }
+    }
+
+    fn schemas() -> Vec<ComponentSchema> {
+        let mut led_properties = HashMap::new();
+        led_properties.insert(
+            "color".to_string(),
+            property(
+                "color",
+                ComponentPropertyValueKind::Predefined(vec![ComponentPropertyPredefinedValue {
+                    type_name: "red".to_string(),
+                    name: "Red".to_string(),
+                    description: String::new(),
+                }]),
+            ),
+        );
+        led_properties.insert(
+            "label".to_string(),
+            property("label", ComponentPropertyValueKind::Custom),
+        );
+        led_properties.insert(
+            "brightness".to_string(),
+            property(
+                "brightness",
+                ComponentPropertyValueKind::Integer(ComponentPropertyRange {
+                    min: Some(0.0),
+                    max: Some(255.0),
+                    step: None,
+                }),
+            ),
+        );
+        led_properties.insert(
+            "interval".to_string(),
+            property(
+                "interval",
+                ComponentPropertyValueKind::Duration(ComponentPropertyRange::default()),
+            ),
+        );
+
+        let mut led_actions = HashMap::new();
+        led_actions.insert(
+            "toggle".to_string(),
+            ComponentAction {
+                type_name: "toggle".to_string(),
+                name: "Toggle".to_string(),
+                description: String::new(),
+            },
+        );
+
+        let mut button_properties = HashMap::new();
+        button_properties.insert(
+            "led".to_string(),
+            property(
+                "led",
+                ComponentPropertyValueKind::Component(vec!["led".to_string()]),
+            ),
+        );
+
+        let mut button_triggers = HashMap::new();
+        button_triggers.insert(
+            "pressed".to_string(),
+            ComponentTrigger {
+                type_name: "pressed".to_string(),
+                name: "Pressed".to_string(),
+                description: String::new(),
+            },
+        );
+
+        let mut led = schema("led");
+        led.properties = led_properties;
+        led.actions = led_actions;
+
+        let mut button = schema("button");
+        button.properties = button_properties;
+        button.triggers = button_triggers;
+
+        vec![led, button]
+    }
+
+    fn validator() -> ProjectValidator {
+        let capabilities = ["wifi", "bluetooth"]
+            .iter()
+            .map(|type_name| ProjectCapability {
+                type_name: type_name.to_string(),
+                name: type_name.to_string(),
+                description: String::new(),
+            }).collect();
+
+        let platforms = vec![ProjectPlatform {
+            type_name: "arduino".to_string(),
+            name: "Arduino".to_string(),
+            description: String::new(),
+            capabilities: vec!["wifi".to_string()],
+        }];
+
+        let mut led_v1 = schemas().remove(0);
+        led_v1.properties.remove("brightness");
+        led_v1.actions.clear();
+
+        let mut schemas = schemas();
+        schemas[0].version = 2;
+
+        ProjectValidator::new(schemas, vec![led_v1], capabilities, platforms)
+    }
+
+    #[test]
+    fn valid_project_should_pass_validation() {
+        let mut button = component("button-1", "button", &[("led", "led-1")]);
+        button.triggers.insert(
+            "pressed".to_string(),
+            vec![ComponentTriggerAction {
+                action: "toggle".to_string(),
+                component: "led-1".to_string(),
+            }],
+        );
+
+        let project = project(
+            "",
+            "project",
+            vec![
+                component("led-1", "led", &[("color", "red"), ("label", "any")]),
+                button,
+            ],
+        );
+
+        assert!(validator().validate(&project).is_ok());
+    }
+
+    #[test]
+    fn invalid_project_should_report_all_errors() {
+        let mut button = component("button-1", "button", &[("led", "button-1")]);
+        button.triggers.insert(
+            "pressed".to_string(),
+            vec![
+                ComponentTriggerAction {
+                    action: "blink".to_string(),
+                    component: "led-1".to_string(),
+                },
+                ComponentTriggerAction {
+                    action: "toggle".to_string(),
+                    component: "led-2".to_string(),
+                },
+            ],
+        );
+        button.triggers.insert("released".to_string(), vec![]);
+
+        let mut project = project(
+            "",
+            "project",
+            vec![
+                component("led-1", "led", &[("color", "blue"), ("size", "big")]),
+                button,
+                component("motor-1", "motor", &[]),
+            ],
+        );
+        project.capabilities = vec!["wifi".to_string(), "bluetooth".to_string(), "lte".to_string()];
+
+        let mut errors = validator().validate(&project).unwrap_err().errors;
+        errors.sort_by(|error_a, error_b| error_a.path.cmp(&error_b.path));
+
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::new(
+                    "/capabilities/1",
+                    "Capability `bluetooth` is not supported by `arduino` platform.",
+                ),
+                ValidationError::new("/capabilities/2", "Unknown capability `lte`."),
+                ValidationError::new(
+                    "/components/0/properties/color",
+                    "Value `blue` is not one of the predefined options: `red`.",
+                ),
+                ValidationError::new(
+                    "/components/0/properties/size",
+                    "Property `size` is not declared by `led` component schema.",
+                ),
+                ValidationError::new(
+                    "/components/1/properties/led",
+                    "Component `button-1` has type `button`, but one of `led` is expected.",
+                ),
+                ValidationError::new(
+                    "/components/1/triggers/pressed/0/action",
+                    "Action `blink` is not declared by `led` component schema.",
+                ),
+                ValidationError::new(
+                    "/components/1/triggers/pressed/1/component",
+                    "Component `led-2` doesn't exist.",
+                ),
+                ValidationError::new(
+                    "/components/1/triggers/released",
+                    "Trigger `released` is not declared by `button` component schema.",
+                ),
+                ValidationError::new("/components/2/type", "Unknown component type `motor`."),
+            ]
+        );
+    }
+
+    #[test]
+    fn typed_properties_should_be_normalized() {
+        let validator = validator();
+        let mut project = project(
+            "",
+            "project",
+            vec![component(
+                "led-1",
+                "led",
+                &[("brightness", "+042"), ("interval", "1.5s")],
+            )],
+        );
+
+        validator.normalize(&mut project);
+        assert_eq!(project.components[0].properties["brightness"], "42");
+        assert_eq!(project.components[0].properties["interval"], "1500ms");
+        assert!(validator.validate(&project).is_ok());
+
+        project.components[0]
+            .properties
+            .insert("brightness".to_string(), "300".to_string());
+        validator.normalize(&mut project);
+        assert_eq!(project.components[0].properties["brightness"], "300");
+        assert_eq!(
+            validator.validate(&project).unwrap_err().errors,
+            vec![ValidationError::new(
+                "/components/0/properties/brightness",
+                "Value `300` is greater than maximum `255`.",
+            )]
+        );
+    }
+
+    #[test]
+    fn missing_properties_should_be_filled_with_defaults() {
+        let mut schemas = schemas();
+        for (property_name, default_value) in &[("color", "red"), ("interval", "2s")] {
+            schemas[0]
+                .properties
+                .get_mut(*property_name)
+                .unwrap()
+                .default_value = default_value.to_string();
+        }
+
+        let validator = ProjectValidator::new(schemas, vec![], vec![], vec![]);
+        let mut project = project(
+            "",
+            "project",
+            vec![
+                component("led-1", "led", &[("color", "red"), ("label", "status")]),
+                component("led-2", "led", &[]),
+                component("motor-1", "motor", &[]),
+            ],
+        );
+        validator.fill_defaults(&mut project);
+
+        let led_one = &project.components[0].properties;
+        assert_eq!(led_one.len(), 3);
+        assert_eq!(led_one["interval"], "2000ms");
+
+        // Properties without default values are left unset.
+        let led_two = &project.components[1].properties;
+        assert_eq!(led_two.len(), 2);
+        assert_eq!(led_two["color"], "red");
+        assert_eq!(led_two["interval"], "2000ms");
+
+        assert!(project.components[2].properties.is_empty());
+    }
+
+    #[test]
+    fn components_should_be_validated_against_pinned_schema_version() {
+        let validator = validator();
+
+        let mut unpinned_project = project(
+            "",
+            "project",
+            vec![component("led-1", "led", &[("brightness", "10")])],
+        );
+        validator.normalize(&mut unpinned_project);
+        assert_eq!(unpinned_project.components[0].schema_version, Some(2));
+        assert!(validator.validate(&unpinned_project).is_ok());
+
+        let mut led = component("led-1", "led", &[("brightness", "10")]);
+        led.schema_version = Some(1);
+        let mut button = component("button-1", "button", &[]);
+        button.triggers.insert(
+            "pressed".to_string(),
+            vec![ComponentTriggerAction {
+                action: "toggle".to_string(),
+                component: "led-1".to_string(),
+            }],
+        );
+        let mut unknown_version = component("led-2", "led", &[]);
+        unknown_version.schema_version = Some(5);
+
+        let project = project("", "project", vec![led, button, unknown_version]);
+        assert_eq!(
+            validator.validate(&project).unwrap_err().errors,
+            vec![
+                ValidationError::new(
+                    "/components/0/properties/brightness",
+                    "Property `brightness` is not declared by `led` component schema.",
+                ),
+                ValidationError::new(
+                    "/components/1/triggers/pressed/0/action",
+                    "Action `toggle` is not declared by `led` component schema.",
+                ),
+                ValidationError::new(
+                    "/components/2/schemaVersion",
+                    "Version 5 of `led` component schema doesn't exist.",
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_platform_should_be_reported() {
+        let mut project = project("", "project", vec![]);
+        project.platform = "raspberry".to_string();
+        project.capabilities = vec!["wifi".to_string()];
+
+        assert_eq!(
+            validator().validate(&project).unwrap_err().errors,
+            vec![ValidationError::new(
+                "/platform",
+                "Unknown platform `raspberry`."
+            )]
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use std::fmt;
+
+use failure::Fail;
+
+/// Describes single validation error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    /// JSON Pointer (RFC 6901) to the invalid value, e.g. `/components/0/type`.
+    pub path: String,
+    /// Human-readable description of the error.
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new<P: Into<String>, M: Into<String>>(path: P, message: M) -> Self {
+        ValidationError {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Describes all validation errors found in the validated entity.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ValidationErrors {
+    /// List of the validation errors.
+    pub errors: Vec<ValidationError>,
+}
+
+impl ValidationErrors {
+    /// Returns `Ok` if there are no errors in the list and `Err` with all the errors otherwise.
+    pub fn into_result(errors: Vec<ValidationError>) -> Result<(), ValidationErrors> {
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors { errors })
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Validation failed with {} error(s)", self.errors.len())
+    }
+}
+
+impl Fail for ValidationErrors {}